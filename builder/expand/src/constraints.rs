@@ -0,0 +1,243 @@
+use crate::named_field::NamedFieldData;
+use crate::options::{Group, GroupRule};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+
+/// A cross-field rule checked by the generated `build()`
+pub enum Constraint<'a> {
+    /// `#[builder(group(...))]`
+    Group {
+        group: &'a Group,
+        fields: Vec<&'a NamedFieldData>,
+    },
+    /// `#[builder(requires = ...)]`
+    Requires {
+        field: &'a NamedFieldData,
+        requires: &'a NamedFieldData,
+    },
+    /// `#[builder(conflicts_with = ...)]`
+    ConflictsWith {
+        field: &'a NamedFieldData,
+        conflicts_with: &'a NamedFieldData,
+    },
+}
+
+/// Resolve the field names used by groups and by `requires`/`conflicts_with` into constraints
+pub fn extract<'a>(
+    groups: &'a [Group],
+    struct_fields: &'a [NamedFieldData],
+) -> syn::Result<Vec<Constraint<'a>>> {
+    let find = |name: &syn::Ident| -> syn::Result<&'a NamedFieldData> {
+        let Some(field) = struct_fields.iter().find(|field| field.name == *name) else {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("unknown field `{name}`"),
+            ));
+        };
+        if field.as_is_set().is_none() {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("`{name}` is skipped or computed, it cannot be set through the builder"),
+            ));
+        }
+        Ok(field)
+    };
+
+    let mut constraints = Vec::new();
+    for group in groups {
+        let fields = group.fields.iter().map(find).collect::<syn::Result<_>>()?;
+        constraints.push(Constraint::Group { group, fields });
+    }
+    for field in struct_fields {
+        if field.as_is_set().is_none()
+            && let Some(name) = field.requires.iter().chain(&field.conflicts_with).next()
+        {
+            return Err(syn::Error::new(
+                name.span(),
+                "skipped and computed fields cannot have `requires` or `conflicts_with`",
+            ));
+        }
+        for requires in &field.requires {
+            let requires = find(requires)?;
+            constraints.push(Constraint::Requires { field, requires });
+        }
+        for conflicts_with in &field.conflicts_with {
+            let conflicts_with = find(conflicts_with)?;
+            constraints.push(Constraint::ConflictsWith {
+                field,
+                conflicts_with,
+            });
+        }
+    }
+
+    Ok(constraints)
+}
+
+fn field_name(field: &NamedFieldData) -> String {
+    field.name.unraw().to_string()
+}
+
+impl Constraint<'_> {
+    /// Produce a statement returning a `BuildError` from `build()` if the constraint is violated
+    pub fn as_check(&self) -> TokenStream {
+        let error = self.error();
+        self.if_violated(quote! {
+            return ::std::result::Result::Err(::std::convert::Into::into(#error));
+        })
+    }
+
+    /// Produce a statement panicking in a `const fn build()` if the constraint is violated
+    pub fn as_const_check(&self) -> TokenStream {
+        let message = self.message();
+        self.if_violated(quote! {
+            ::std::panic!(#message);
+        })
+    }
+
+    /// Produce a statement returning `false` from `is_complete()` if the constraint is violated
+    pub fn as_complete_check(&self) -> TokenStream {
+        self.if_violated(quote! {
+            return false;
+        })
+    }
+
+    /// Produce a statement running `body` if the constraint is violated
+    ///
+    /// Group constraints count their set fields into a `set` variable first, which `body` can
+    /// refer to.
+    fn if_violated(&self, body: TokenStream) -> TokenStream {
+        match self {
+            Constraint::Group { group, fields } => {
+                let is_set = fields.iter().filter_map(|field| field.as_is_set());
+                let condition = match group.rule {
+                    GroupRule::ExactlyOne => quote!(set != 1),
+                    GroupRule::AtLeastOne => quote!(set == 0),
+                    GroupRule::AtMostOne => quote!(set > 1),
+                };
+                quote! {
+                    {
+                        let set = 0 #(+ (#is_set) as usize)*;
+                        if #condition {
+                            #body
+                        }
+                    }
+                }
+            }
+            Constraint::Requires { field, requires } => {
+                let field_is_set = field.as_is_set();
+                let requires_is_set = requires.as_is_set();
+                quote! {
+                    if #field_is_set && !(#requires_is_set) {
+                        #body
+                    }
+                }
+            }
+            Constraint::ConflictsWith {
+                field,
+                conflicts_with,
+            } => {
+                let field_is_set = field.as_is_set();
+                let conflicts_with_is_set = conflicts_with.as_is_set();
+                quote! {
+                    if #field_is_set && #conflicts_with_is_set {
+                        #body
+                    }
+                }
+            }
+        }
+    }
+
+    fn error(&self) -> TokenStream {
+        match self {
+            Constraint::Group { group, fields } => {
+                let name = &group.name;
+                let fields = fields.iter().map(|field| field_name(field));
+                let fields = quote!(&[#(#fields),*]);
+                match group.rule {
+                    GroupRule::ExactlyOne => quote! {
+                        ::derive_builder::BuildError::ExactlyOne {
+                            group: #name,
+                            fields: #fields,
+                            set,
+                        }
+                    },
+                    GroupRule::AtLeastOne => quote! {
+                        ::derive_builder::BuildError::AtLeastOne {
+                            group: #name,
+                            fields: #fields,
+                        }
+                    },
+                    GroupRule::AtMostOne => quote! {
+                        ::derive_builder::BuildError::AtMostOne {
+                            group: #name,
+                            fields: #fields,
+                            set,
+                        }
+                    },
+                }
+            }
+            Constraint::Requires { field, requires } => {
+                let field = field_name(field);
+                let requires = field_name(requires);
+                quote! {
+                    ::derive_builder::BuildError::Requires {
+                        field: #field,
+                        requires: #requires,
+                    }
+                }
+            }
+            Constraint::ConflictsWith {
+                field,
+                conflicts_with,
+            } => {
+                let field = field_name(field);
+                let conflicts_with = field_name(conflicts_with);
+                quote! {
+                    ::derive_builder::BuildError::ConflictsWith {
+                        field: #field,
+                        conflicts_with: #conflicts_with,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Like the `Display` of `BuildError`, but known at expansion time, for `const fn build()`
+    fn message(&self) -> String {
+        match self {
+            Constraint::Group { group, fields } => {
+                let fields = fields
+                    .iter()
+                    .map(|field| format!("`{}`", field_name(field)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let name = group.name.value();
+                match group.rule {
+                    GroupRule::ExactlyOne => {
+                        format!("exactly one of {fields} must be set for group `{name}`")
+                    }
+                    GroupRule::AtLeastOne => {
+                        format!("at least one of {fields} must be set for group `{name}`")
+                    }
+                    GroupRule::AtMostOne => {
+                        format!("at most one of {fields} may be set for group `{name}`")
+                    }
+                }
+            }
+            Constraint::Requires { field, requires } => format!(
+                "field `{}` requires field `{}` to be set",
+                field_name(field),
+                field_name(requires),
+            ),
+            Constraint::ConflictsWith {
+                field,
+                conflicts_with,
+            } => format!(
+                "field `{}` conflicts with field `{}`",
+                field_name(field),
+                field_name(conflicts_with),
+            ),
+        }
+    }
+}
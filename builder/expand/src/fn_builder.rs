@@ -0,0 +1,328 @@
+use crate::constraints::{self, Constraint};
+use crate::named_field::NamedFieldData;
+use crate::util;
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, format_ident, quote};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+
+/// Expand `#[fn_builder]` on a free function, or on an impl block containing methods marked with
+/// an inner `#[fn_builder]`
+pub fn execute(args: TokenStream, mut input: syn::Item) -> syn::Result<TokenStream> {
+    if !args.is_empty() {
+        return Err(syn::Error::new(
+            args.span(),
+            "`fn_builder` takes no arguments",
+        ));
+    }
+
+    match &mut input {
+        syn::Item::Fn(item_fn) => {
+            if let Some(receiver) = item_fn.sig.receiver() {
+                return Err(syn::Error::new(
+                    receiver.span(),
+                    "to build the arguments of a method, place `#[fn_builder]` on the enclosing impl block as well",
+                ));
+            }
+            // without a receiver, only `Self` tells an associated function from a free one
+            let (inputs, output) = (&item_fn.sig.inputs, &item_fn.sig.output);
+            if let Some(span) = util::find_self_ty(quote!(#inputs #output)) {
+                return Err(syn::Error::new(
+                    span,
+                    "to build the arguments of an associated function, place `#[fn_builder]` on the enclosing impl block as well",
+                ));
+            }
+
+            let target = FnTarget::Free;
+            let builder = fn_builder(&target, &item_fn.vis, &mut item_fn.sig)?;
+            let mut stream = input.into_token_stream();
+            stream.extend(builder.items);
+            stream.extend(builder.constructor);
+            Ok(stream)
+        }
+        syn::Item::Impl(item_impl) => {
+            if let Some((_, trait_path, _)) = &item_impl.trait_ {
+                return Err(syn::Error::new(
+                    trait_path.span(),
+                    "`fn_builder` is not supported on trait impls",
+                ));
+            }
+            if !item_impl.generics.params.is_empty() {
+                return Err(syn::Error::new(
+                    item_impl.generics.span(),
+                    "`fn_builder` is not supported on generic impls",
+                ));
+            }
+            let syn::Type::Path(self_ty) = &*item_impl.self_ty else {
+                return Err(syn::Error::new(
+                    item_impl.self_ty.span(),
+                    "expected a named type",
+                ));
+            };
+            let self_ty_path = self_ty.clone();
+
+            let mut items = TokenStream::new();
+            let mut constructors = TokenStream::new();
+            for impl_item in &mut item_impl.items {
+                let syn::ImplItem::Fn(method) = impl_item else {
+                    continue;
+                };
+                // the attribute may be written as `#[derive_builder::fn_builder]` as well
+                let marked = method
+                    .attrs
+                    .extract_if(.., |attr| {
+                        attr.path()
+                            .segments
+                            .last()
+                            .is_some_and(|segment| segment.ident == "fn_builder")
+                    })
+                    .count();
+                if marked == 0 {
+                    continue;
+                }
+
+                let target = FnTarget::Method(&self_ty_path);
+                let builder = fn_builder(&target, &method.vis, &mut method.sig)?;
+                items.extend(builder.items);
+                constructors.extend(builder.constructor);
+            }
+
+            let mut stream = item_impl.to_token_stream();
+            stream.extend(items);
+            if !constructors.is_empty() {
+                stream.extend(quote! {
+                    impl #self_ty_path {
+                        #constructors
+                    }
+                });
+            }
+            Ok(stream)
+        }
+        _ => Err(syn::Error::new(
+            Span::call_site(),
+            "expected function or impl block",
+        )),
+    }
+}
+
+/// Where the function being built lives
+enum FnTarget<'a> {
+    Free,
+    /// A method or associated function of the given type
+    Method(&'a syn::TypePath),
+}
+
+/// How the function being built takes `self`
+enum FnReceiver {
+    /// Not a method
+    None,
+    /// `&self`
+    Ref,
+    /// `&mut self`
+    RefMut,
+}
+
+struct FnBuilder {
+    /// The builder struct and its impl block
+    items: TokenStream,
+    /// The function producing the builder, either free or an associated function of the type the
+    /// method belongs to
+    constructor: TokenStream,
+}
+
+fn fn_builder(
+    target: &FnTarget,
+    vis: &syn::Visibility,
+    sig: &mut syn::Signature,
+) -> syn::Result<FnBuilder> {
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            "`fn_builder` is not supported on generic functions",
+        ));
+    }
+    if let Some(unsafety) = &sig.unsafety {
+        return Err(syn::Error::new(
+            unsafety.span(),
+            "`fn_builder` is not supported on unsafe functions",
+        ));
+    }
+
+    let receiver = match sig.receiver() {
+        None => FnReceiver::None,
+        Some(receiver) if receiver.colon_token.is_none() && receiver.reference.is_some() => {
+            if receiver.mutability.is_some() {
+                FnReceiver::RefMut
+            } else {
+                FnReceiver::Ref
+            }
+        }
+        Some(receiver) => {
+            return Err(syn::Error::new(
+                receiver.span(),
+                "`fn_builder` only supports `&self` and `&mut self` receivers",
+            ));
+        }
+    };
+
+    let self_ty = match target {
+        FnTarget::Free => None,
+        FnTarget::Method(self_ty) => Some(*self_ty),
+    };
+    let builder_lifetime = syn::Lifetime::new("'a", Span::call_site());
+    let (args, borrows) = extract_args(sig, self_ty, &builder_lifetime)?;
+    let constraints = constraints::extract(&[], &args)?;
+    crate::check_method_names(
+        &["call"],
+        args.iter()
+            .filter_map(|arg| Some((arg.setter_name()?.clone(), &arg.name))),
+    )?;
+
+    let fn_name = &sig.ident;
+    let builder_name = match target {
+        FnTarget::Free => format!("{}Builder", util::to_pascal_case(&fn_name.to_string())),
+        FnTarget::Method(self_ty) => format!(
+            "{}{}Builder",
+            self_ty.path.segments.last().unwrap().ident,
+            util::to_pascal_case(&fn_name.to_string()),
+        ),
+    };
+    let builder_name = syn::Ident::new(&builder_name, fn_name.span());
+    let constructor_name = format_ident!("{}_builder", fn_name.unraw());
+
+    let (receiver_field, receiver_arg, receiver_initializer) = match (target, &receiver) {
+        (FnTarget::Method(self_ty), FnReceiver::Ref) => (
+            Some(quote!(receiver: &#builder_lifetime #self_ty,)),
+            quote!(&#builder_lifetime self),
+            Some(quote!(receiver: self,)),
+        ),
+        (FnTarget::Method(self_ty), FnReceiver::RefMut) => (
+            Some(quote!(receiver: &#builder_lifetime mut #self_ty,)),
+            quote!(&#builder_lifetime mut self),
+            Some(quote!(receiver: self,)),
+        ),
+        (_, _) => (None, quote!(), None),
+    };
+    // the builder borrows the receiver and any reference arguments for the same lifetime
+    let lifetime = (receiver_field.is_some() || borrows).then(|| quote!(<#builder_lifetime>));
+    if receiver_field.is_some()
+        && let Some(arg) = args.iter().find(|arg| arg.name == "receiver")
+    {
+        return Err(syn::Error::new(
+            arg.name.span(),
+            "`receiver` is reserved for the `self` of the method being built",
+        ));
+    }
+
+    let builder_fields = args.iter().filter_map(NamedFieldData::as_optional_field);
+    let initializers = args.iter().filter_map(NamedFieldData::as_field_initializer);
+    let setters = args.iter().filter_map(NamedFieldData::as_setter_fn);
+    let checks = constraints.iter().map(Constraint::as_check);
+    let bindings = crate::build_bindings(&args, NamedFieldData::as_build_binding);
+    let arg_names = args.iter().map(|arg| &arg.name);
+
+    let output = match (&sig.output, self_ty) {
+        (syn::ReturnType::Default, _) => quote!(()),
+        (syn::ReturnType::Type(_, ty), None) => ty.to_token_stream(),
+        (syn::ReturnType::Type(_, ty), Some(self_ty)) => {
+            util::replace_self_ty(ty, self_ty).to_token_stream()
+        }
+    };
+    let call_receiver = match receiver {
+        FnReceiver::RefMut => quote!(&mut self),
+        FnReceiver::None | FnReceiver::Ref => quote!(&self),
+    };
+    let callee = match (target, &receiver) {
+        (FnTarget::Free, _) => quote!(#fn_name),
+        (FnTarget::Method(_), FnReceiver::Ref | FnReceiver::RefMut) => {
+            quote!(self.receiver.#fn_name)
+        }
+        (FnTarget::Method(self_ty), FnReceiver::None) => quote!(#self_ty::#fn_name),
+    };
+    let (asyncness, await_call) = match &sig.asyncness {
+        Some(asyncness) => (Some(asyncness), Some(quote!(.await))),
+        None => (None, None),
+    };
+
+    let items = quote! {
+        #vis struct #builder_name #lifetime {
+            #receiver_field
+            #(#builder_fields),*
+        }
+
+        impl #lifetime #builder_name #lifetime {
+            #(#setters)*
+
+            pub #asyncness fn call(#call_receiver) -> ::std::result::Result<#output, ::std::boxed::Box<dyn ::std::error::Error>> {
+                #(#checks)*
+                #(#bindings)*
+                ::std::result::Result::Ok(#callee(#(#arg_names),*) #await_call)
+            }
+        }
+    };
+    let constructor = quote! {
+        #vis fn #constructor_name #lifetime (#receiver_arg) -> #builder_name #lifetime {
+            #builder_name {
+                #receiver_initializer
+                #(#initializers),*
+            }
+        }
+    };
+
+    Ok(FnBuilder { items, constructor })
+}
+
+/// Collect the arguments of the function, removing their `#[builder(...)]` attributes so the
+/// function itself still compiles
+///
+/// For methods, `Self` in argument types is replaced by `self_ty`, since the arguments are stored
+/// outside of the impl block. Elided lifetimes are named `lifetime`, the lifetime of the builder,
+/// and the returned flag tells whether there were any.
+fn extract_args(
+    sig: &mut syn::Signature,
+    self_ty: Option<&syn::TypePath>,
+    lifetime: &syn::Lifetime,
+) -> syn::Result<(Vec<NamedFieldData>, bool)> {
+    let mut args = Vec::new();
+    let mut borrows = false;
+    for input in &mut sig.inputs {
+        let syn::FnArg::Typed(pat_type) = input else {
+            continue;
+        };
+        let syn::Pat::Ident(pat_ident) = &*pat_type.pat else {
+            return Err(syn::Error::new(
+                pat_type.pat.span(),
+                "`fn_builder` requires every argument to be a plain identifier",
+            ));
+        };
+        if let Some(cfg) = pat_type
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("cfg") || attr.path().is_ident("cfg_attr"))
+        {
+            return Err(syn::Error::new(
+                cfg.span(),
+                "`fn_builder` does not support conditionally compiled arguments",
+            ));
+        }
+
+        let attrs = pat_type
+            .attrs
+            .extract_if(.., |attr| attr.path().is_ident("builder"))
+            .collect::<Vec<_>>();
+        let mut ty = match self_ty {
+            Some(self_ty) => util::replace_self_ty(&pat_type.ty, self_ty),
+            None => (*pat_type.ty).clone(),
+        };
+        borrows |= util::name_elided_lifetimes(&mut ty, lifetime);
+        let arg = NamedFieldData::new(pat_ident.ident.clone(), &ty, &attrs)?;
+        if let Some(redact) = &arg.redact {
+            return Err(syn::Error::new(
+                redact.span(),
+                "`fn_builder` does not support `redact`",
+            ));
+        }
+        args.push(arg);
+    }
+    Ok((args, borrows))
+}
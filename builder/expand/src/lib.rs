@@ -0,0 +1,441 @@
+// The implementation of `#[derive(Builder)]` and `#[fn_builder]`, usable outside of macro expansion.
+
+mod constraints;
+mod fn_builder;
+mod named_field;
+mod options;
+mod util;
+
+use constraints::Constraint;
+use named_field::{NamedFieldData, NamedFieldKind};
+use options::BuilderOptions;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::HashMap;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+
+/// Expand `#[derive(Builder)]` on the given input
+///
+/// This is the whole implementation of the derive macro, kept outside of the proc-macro crate
+/// so the generated code can be inspected from ordinary tests.
+pub fn derive_builder(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+
+    let builder_name = syn::Ident::new(&format!("{name}Builder"), name.span());
+
+    let options = options::extract_from_derive_input(&input)?;
+    let struct_fields = named_field::extract_from_derive_input(&input)?;
+    let constraints = constraints::extract(&options.groups, &struct_fields)?;
+    check_method_names(
+        &options.method_names(),
+        struct_fields.iter().flat_map(|field| {
+            let setter = field.setter_name().cloned();
+            setter
+                .into_iter()
+                .chain(field.unset_fn_name())
+                .map(move |method| (method, &field.name))
+        }),
+    )?;
+
+    let builder = builder(vis, &builder_name, &struct_fields);
+    let builder_initializer = builder_initializer(name, &builder_name, &struct_fields, &options);
+    let builder_impl = if options.const_fn.is_some() {
+        const_builder_impl(name, &builder_name, &struct_fields, &constraints)?
+    } else {
+        builder_impl(name, &builder_name, &struct_fields, &constraints, &options)
+    };
+    let trait_impls = trait_impls(name, &builder_name, &struct_fields, &constraints, &options);
+    let debug_impl = debug_impl(&builder_name, &struct_fields, &options)?;
+
+    Ok(output(
+        builder,
+        builder_initializer,
+        builder_impl,
+        trait_impls,
+        debug_impl,
+    ))
+}
+
+/// Expand `#[fn_builder]` on the given item, with the given attribute arguments
+///
+/// Like [`derive_builder`], this is the whole implementation of the attribute macro.
+pub fn fn_builder(args: TokenStream, input: syn::Item) -> syn::Result<TokenStream> {
+    fn_builder::execute(args, input)
+}
+
+/// Report a builder method generated for a field that has the name of another builder method
+///
+/// `reserved` are the methods every builder of this kind has, and `methods` pairs the methods
+/// generated for fields with their field.
+fn check_method_names<'a>(
+    reserved: &[&str],
+    methods: impl IntoIterator<Item = (syn::Ident, &'a syn::Ident)>,
+) -> syn::Result<()> {
+    let mut generated = HashMap::new();
+    for (method, field) in methods {
+        let name = method.unraw().to_string();
+        if reserved.contains(&name.as_str()) {
+            return Err(syn::Error::new(
+                method.span(),
+                format!("`{name}` is already a method of the builder"),
+            ));
+        }
+        if let Some(other) = generated.insert(name.clone(), field) {
+            return Err(syn::Error::new(
+                method.span(),
+                format!(
+                    "`{name}` is generated for both field `{}` and field `{}`",
+                    other.unraw(),
+                    field.unraw(),
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn output(
+    builder: TokenStream,
+    builder_initializer: TokenStream,
+    build_impl: TokenStream,
+    trait_impls: TokenStream,
+    debug_impl: Option<TokenStream>,
+) -> TokenStream {
+    quote! {
+        #builder
+        #builder_initializer
+        #build_impl
+        #trait_impls
+        #debug_impl
+    }
+}
+
+fn builder(
+    vis: &syn::Visibility,
+    builder_name: &syn::Ident,
+    struct_fields: &[NamedFieldData],
+) -> TokenStream {
+    let builder_fields = struct_fields
+        .iter()
+        .filter_map(NamedFieldData::as_optional_field);
+
+    quote! {
+        #vis struct #builder_name {
+            #(#builder_fields),*
+        }
+    }
+}
+
+fn builder_initializer(
+    name: &syn::Ident,
+    builder_name: &syn::Ident,
+    struct_fields: &[NamedFieldData],
+    options: &BuilderOptions,
+) -> TokenStream {
+    let initializers = struct_fields
+        .iter()
+        .filter_map(NamedFieldData::as_field_initializer);
+    let constness = options.const_fn.as_ref().map(|_| quote!(const));
+
+    quote! {
+        impl #name {
+            pub #constness fn builder() -> #builder_name {
+                #builder_name {
+                    #(#initializers),*
+                }
+            }
+        }
+    }
+}
+
+fn builder_impl(
+    name: &syn::Ident,
+    builder_name: &syn::Ident,
+    struct_fields: &[NamedFieldData],
+    constraints: &[Constraint],
+    options: &BuilderOptions,
+) -> TokenStream {
+    let setters = struct_fields
+        .iter()
+        .filter_map(NamedFieldData::as_setter_fn);
+    let unset_fns = struct_fields.iter().filter_map(NamedFieldData::as_unset_fn);
+    let build_fn = build_fn(name, struct_fields, constraints);
+    let is_complete_fn = is_complete_fn(struct_fields, constraints, None);
+    let env_fns = options
+        .env_prefix
+        .as_ref()
+        .map(|env_prefix| env_fns(name, env_prefix, &options.env_separator(), struct_fields));
+    let args_fns = options.args.as_ref().map(|_| args_fns(name, struct_fields));
+
+    quote! {
+        impl #builder_name {
+            #build_fn
+            #is_complete_fn
+
+            pub fn reset(&mut self) -> &mut Self {
+                *self = #name::builder();
+                self
+            }
+
+            #(#setters)*
+            #(#unset_fns)*
+            #env_fns
+            #args_fns
+        }
+    }
+}
+
+fn const_builder_impl(
+    name: &syn::Ident,
+    builder_name: &syn::Ident,
+    struct_fields: &[NamedFieldData],
+    constraints: &[Constraint],
+) -> syn::Result<TokenStream> {
+    for field in struct_fields {
+        field.validate_const()?;
+    }
+
+    let assertions = struct_fields
+        .iter()
+        .filter_map(NamedFieldData::as_const_field_assertion);
+    let setters = struct_fields
+        .iter()
+        .filter_map(NamedFieldData::as_const_setter_fn);
+    let unset_fns = struct_fields
+        .iter()
+        .filter_map(NamedFieldData::as_const_unset_fn);
+    let is_complete_fn = is_complete_fn(struct_fields, constraints, Some(quote!(const)));
+    let checks = constraints.iter().map(Constraint::as_const_check);
+    let bindings = build_bindings(struct_fields, NamedFieldData::as_const_build_binding);
+    let field_names = struct_fields.iter().map(NamedFieldData::as_field_shorthand);
+
+    Ok(quote! {
+        impl #builder_name {
+            pub const fn build(self) -> #name {
+                #(#assertions)*
+                #(#checks)*
+                #(#bindings)*
+                #name {
+                    #(#field_names),*
+                }
+            }
+            #is_complete_fn
+
+            pub const fn reset(self) -> Self {
+                // dropping `self` in a `const fn` fails for non-`Copy` fields, which `build()`
+                // already reports with a clearer error
+                ::std::mem::forget(self);
+                #name::builder()
+            }
+
+            #(#setters)*
+            #(#unset_fns)*
+        }
+    })
+}
+
+fn build_fn(
+    name: &syn::Ident,
+    struct_fields: &[NamedFieldData],
+    constraints: &[Constraint],
+) -> TokenStream {
+    let build_body = build_body(name, struct_fields, constraints);
+
+    quote! {
+        pub fn build(&self) -> ::std::result::Result<#name, ::std::boxed::Box<dyn ::std::error::Error>> {
+            #build_body
+        }
+    }
+}
+
+/// `is_complete()` tells whether `build()` would succeed, without cloning any field
+fn is_complete_fn(
+    struct_fields: &[NamedFieldData],
+    constraints: &[Constraint],
+    constness: Option<TokenStream>,
+) -> TokenStream {
+    let field_checks = struct_fields
+        .iter()
+        .filter_map(NamedFieldData::as_complete_check);
+    let constraint_checks = constraints.iter().map(Constraint::as_complete_check);
+
+    quote! {
+        pub #constness fn is_complete(&self) -> bool {
+            #(#field_checks)*
+            #(#constraint_checks)*
+            true
+        }
+    }
+}
+
+fn build_body(
+    name: &syn::Ident,
+    struct_fields: &[NamedFieldData],
+    constraints: &[Constraint],
+) -> TokenStream {
+    let checks = constraints.iter().map(Constraint::as_check);
+    let bindings = build_bindings(struct_fields, NamedFieldData::as_build_binding);
+    let field_names = struct_fields.iter().map(NamedFieldData::as_field_shorthand);
+
+    quote! {
+        #(#checks)*
+        #(#bindings)*
+        ::std::result::Result::Ok(#name {
+            #(#field_names),*
+        })
+    }
+}
+
+fn build_bindings(
+    struct_fields: &[NamedFieldData],
+    binding: impl Fn(&NamedFieldData) -> TokenStream,
+) -> impl Iterator<Item = TokenStream> {
+    // computed fields go last so their expressions can refer to every other field
+    let (computed_fields, other_fields): (Vec<_>, Vec<_>) = struct_fields
+        .iter()
+        .partition(|field| matches!(field.kind, NamedFieldKind::Computed(_)));
+    other_fields.into_iter().chain(computed_fields).map(binding)
+}
+
+fn env_fns(
+    name: &syn::Ident,
+    env_prefix: &syn::LitStr,
+    env_separator: &str,
+    struct_fields: &[NamedFieldData],
+) -> TokenStream {
+    let loaders = struct_fields
+        .iter()
+        .filter_map(|field| field.as_env_loader(env_prefix, env_separator));
+
+    quote! {
+        pub fn from_env() -> ::std::result::Result<Self, ::std::boxed::Box<dyn ::std::error::Error>> {
+            let mut builder = #name::builder();
+            builder.apply_env()?;
+            ::std::result::Result::Ok(builder)
+        }
+
+        pub fn apply_env(&mut self) -> ::std::result::Result<&mut Self, ::std::boxed::Box<dyn ::std::error::Error>> {
+            #(#loaders)*
+            ::std::result::Result::Ok(self)
+        }
+    }
+}
+
+fn args_fns(name: &syn::Ident, struct_fields: &[NamedFieldData]) -> TokenStream {
+    let loaders = struct_fields
+        .iter()
+        .filter_map(NamedFieldData::as_args_loader);
+
+    quote! {
+        pub fn from_args() -> ::std::result::Result<Self, ::std::boxed::Box<dyn ::std::error::Error>> {
+            let mut builder = #name::builder();
+            builder.apply_args(::std::env::args().skip(1))?;
+            ::std::result::Result::Ok(builder)
+        }
+
+        pub fn apply_args<I>(&mut self, args: I) -> ::std::result::Result<&mut Self, ::std::boxed::Box<dyn ::std::error::Error>>
+        where
+            I: ::std::iter::IntoIterator,
+            I::Item: ::std::convert::Into<::std::string::String>,
+        {
+            let mut args = args.into_iter().map(::std::convert::Into::into);
+            while let ::std::option::Option::Some(arg) = args.next() {
+                if !arg.starts_with("--") {
+                    return ::std::result::Result::Err(
+                        ::std::format!("unexpected argument `{}`", arg).into(),
+                    );
+                }
+                let (flag, value) = match arg.split_once('=') {
+                    ::std::option::Option::Some((flag, value)) => {
+                        (::std::borrow::ToOwned::to_owned(flag), ::std::borrow::ToOwned::to_owned(value))
+                    }
+                    ::std::option::Option::None => match args.next() {
+                        ::std::option::Option::Some(value) => (arg, value),
+                        ::std::option::Option::None => {
+                            return ::std::result::Result::Err(
+                                ::std::format!("missing value for `{}`", arg).into(),
+                            );
+                        }
+                    },
+                };
+                match flag.as_str() {
+                    #(#loaders)*
+                    _ => {
+                        return ::std::result::Result::Err(
+                            ::std::format!("unknown argument `{}`", flag).into(),
+                        );
+                    }
+                }
+            }
+            ::std::result::Result::Ok(self)
+        }
+    }
+}
+
+fn trait_impls(
+    name: &syn::Ident,
+    builder_name: &syn::Ident,
+    struct_fields: &[NamedFieldData],
+    constraints: &[Constraint],
+    options: &BuilderOptions,
+) -> TokenStream {
+    // the `const fn build()` of a `builder(const)` builder consumes it and panics on missing
+    // fields, so the trait gets its own fallible body
+    let build_body = if options.const_fn.is_some() {
+        build_body(name, struct_fields, constraints)
+    } else {
+        quote! { #builder_name::build(self) }
+    };
+
+    quote! {
+        impl ::derive_builder::Buildable for #name {
+            type Builder = #builder_name;
+
+            fn builder() -> #builder_name {
+                #name::builder()
+            }
+        }
+
+        impl ::derive_builder::Builder for #builder_name {
+            type Target = #name;
+            type Error = ::std::boxed::Box<dyn ::std::error::Error>;
+
+            fn build(&self) -> ::std::result::Result<#name, Self::Error> {
+                #build_body
+            }
+        }
+    }
+}
+
+fn debug_impl(
+    builder_name: &syn::Ident,
+    struct_fields: &[NamedFieldData],
+    options: &BuilderOptions,
+) -> syn::Result<Option<TokenStream>> {
+    if options.debug.is_none() {
+        if let Some(redact) = struct_fields.iter().find_map(|field| field.redact.as_ref()) {
+            return Err(syn::Error::new(
+                redact.span(),
+                "`redact` requires `builder(debug)` on the struct",
+            ));
+        }
+        return Ok(None);
+    }
+
+    let label = builder_name.to_string();
+    let fields = struct_fields
+        .iter()
+        .filter_map(NamedFieldData::as_debug_field);
+
+    Ok(Some(quote! {
+        impl ::std::fmt::Debug for #builder_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                let mut debug = f.debug_struct(#label);
+                #(#fields)*
+                debug.finish()
+            }
+        }
+    }))
+}
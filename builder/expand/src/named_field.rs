@@ -0,0 +1,639 @@
+use crate::options;
+use crate::util;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+
+/// Extract named struct field info from derive input data
+pub fn extract_from_derive_input(input: &syn::DeriveInput) -> syn::Result<Vec<NamedFieldData>> {
+    let syn::Data::Struct(data_struct) = &input.data else {
+        return Err(syn::Error::new(input.ident.span(), "expected struct"));
+    };
+    let syn::Fields::Named(struct_fields) = &data_struct.fields else {
+        return Err(syn::Error::new(
+            data_struct.fields.span(),
+            "expected non-tuple struct",
+        ));
+    };
+
+    struct_fields
+        .named
+        .iter()
+        .map(NamedFieldData::try_from)
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum NamedFieldKind {
+    Normal,
+    Option(syn::Type),
+    /// `Option<T>` marked `required`, must be set to `Some` or `None` explicitly
+    RequiredOption(syn::Type),
+    VecWithEach(syn::Type, syn::Ident),
+    /// Not stored in the builder, built from the expression or `Default::default()`
+    Skip(Option<syn::Expr>),
+    /// Not stored in the builder, built from the expression after all other fields
+    Computed(syn::Expr),
+}
+
+#[derive(Debug)]
+pub struct NamedFieldData {
+    pub name: syn::Ident,
+    /// The bare type, may be `Option`, `Vec`, or something else
+    pub ty: syn::Type,
+    pub kind: NamedFieldKind,
+    /// `#[builder(requires = ...)]`
+    pub requires: Vec<syn::Ident>,
+    /// `#[builder(conflicts_with = ...)]`
+    pub conflicts_with: Vec<syn::Ident>,
+    /// `#[deprecated]`, forwarded to the setters of this field
+    pub deprecated: Option<syn::Attribute>,
+    /// `#[builder(redact)]`
+    pub redact: Option<syn::Path>,
+}
+
+impl NamedFieldData {
+    /// Prefix a setter with this field's `#[deprecated]` attribute
+    fn with_setter_attrs(&self, setter: TokenStream) -> TokenStream {
+        let deprecated = &self.deprecated;
+        quote! {
+            #deprecated
+            #setter
+        }
+    }
+
+    /// If this field is `Option<T>` or `Vec<T>`, then `T`, otherwise just the field's type
+    fn inner_ty(&self) -> &syn::Type {
+        match &self.kind {
+            NamedFieldKind::Normal | NamedFieldKind::Skip(_) | NamedFieldKind::Computed(_) => {
+                &self.ty
+            }
+            NamedFieldKind::Option(inner_ty)
+            | NamedFieldKind::RequiredOption(inner_ty)
+            | NamedFieldKind::VecWithEach(inner_ty, _) => inner_ty,
+        }
+    }
+
+    /// The name of the setter of this field, which is the `each` setter for `Vec<T>` fields
+    pub fn setter_name(&self) -> Option<&syn::Ident> {
+        match &self.kind {
+            NamedFieldKind::Normal
+            | NamedFieldKind::Option(_)
+            | NamedFieldKind::RequiredOption(_) => Some(&self.name),
+            NamedFieldKind::VecWithEach(_, each_fn_name) => Some(each_fn_name),
+            NamedFieldKind::Skip(_) | NamedFieldKind::Computed(_) => None,
+        }
+    }
+
+    /// The name of the function returning this field to its unset state, `unset_this_field_name`
+    pub fn unset_fn_name(&self) -> Option<syn::Ident> {
+        self.setter_name()?;
+        Some(format_ident!(
+            "unset_{}",
+            self.name.unraw(),
+            span = self.name.span()
+        ))
+    }
+
+    /// Produce a struct field definition for this field
+    ///
+    /// `Option<T>` => `Option<T>`
+    /// `Vec<T>` => `Vec<T>`
+    /// `T` => `Option<T>`
+    /// `required` `Option<T>` => `Option<Option<T>>`
+    ///
+    /// Skipped and computed fields are not stored in the builder.
+    pub fn as_optional_field(&self) -> Option<TokenStream> {
+        let name = &self.name;
+        let field = match &self.kind {
+            NamedFieldKind::Normal | NamedFieldKind::RequiredOption(_) => {
+                let ty = &self.ty;
+                quote! { #name: ::std::option::Option<#ty> }
+            }
+            NamedFieldKind::Option(inner_ty) => quote! { #name: ::std::option::Option<#inner_ty> },
+            NamedFieldKind::VecWithEach(inner_ty, _) => {
+                quote! { #name: ::std::vec::Vec<#inner_ty> }
+            }
+            NamedFieldKind::Skip(_) | NamedFieldKind::Computed(_) => return None,
+        };
+        Some(field)
+    }
+
+    /// Produce an initializer for this field
+    ///
+    /// `Option<T>` => `this_field_name: None`
+    /// `Vec<T>` => `this_field_name: Vec::new()`
+    /// `T` => `this_field_name: None`
+    pub fn as_field_initializer(&self) -> Option<TokenStream> {
+        let name = &self.name;
+        let initializer = match &self.kind {
+            NamedFieldKind::Normal
+            | NamedFieldKind::Option(_)
+            | NamedFieldKind::RequiredOption(_) => {
+                quote! { #name: ::std::option::Option::None }
+            }
+            NamedFieldKind::VecWithEach(_, _) => {
+                quote! { #name: ::std::vec::Vec::new() }
+            }
+            NamedFieldKind::Skip(_) | NamedFieldKind::Computed(_) => return None,
+        };
+        Some(initializer)
+    }
+
+    /// Produce a setter function for this field on the builder
+    ///
+    /// ```ignore
+    /// pub fn this_field_name(&mut self, this_field_name: ThisFieldType) -> &mut Self {
+    ///     self.this_field_name = Some(this_field_name);
+    ///     self
+    /// }
+    /// pub fn each_fn_name(&mut self, this_field_name: ThisFieldType) -> &mut Self {
+    ///     self.this_field_name.push(this_field_name);
+    ///     self
+    /// }
+    /// ```
+    ///
+    /// Setters for `required` `Option<T>` fields take the whole `Option<T>`.
+    pub fn as_setter_fn(&self) -> Option<TokenStream> {
+        let name = &self.name;
+        let ty = self.inner_ty();
+
+        let setter = match &self.kind {
+            NamedFieldKind::RequiredOption(_) => {
+                let ty = &self.ty;
+                quote! {
+                    pub fn #name(&mut self, #name: #ty) -> &mut Self {
+                        self.#name = ::std::option::Option::Some(#name);
+                        self
+                    }
+                }
+            }
+            NamedFieldKind::Normal | NamedFieldKind::Option(_) => {
+                quote! {
+                    pub fn #name(&mut self, #name: #ty) -> &mut Self {
+                        self.#name = ::std::option::Option::Some(#name);
+                        self
+                    }
+                }
+            }
+            NamedFieldKind::VecWithEach(_, each_fn_name) => {
+                quote! {
+                    pub fn #each_fn_name(&mut self, #name: #ty) -> &mut Self {
+                        self.#name.push(#name);
+                        self
+                    }
+                }
+            }
+            NamedFieldKind::Skip(_) | NamedFieldKind::Computed(_) => return None,
+        };
+        Some(self.with_setter_attrs(setter))
+    }
+
+    /// Produce a function returning this field to its initial, unset state
+    ///
+    /// ```ignore
+    /// pub fn unset_this_field_name(&mut self) -> &mut Self {
+    ///     self.this_field_name = None;
+    ///     self
+    /// }
+    /// ```
+    ///
+    /// `Vec<T>` fields are cleared instead.
+    pub fn as_unset_fn(&self) -> Option<TokenStream> {
+        let name = &self.name;
+        let unset_fn_name = self.unset_fn_name()?;
+
+        let unset = match &self.kind {
+            NamedFieldKind::Normal
+            | NamedFieldKind::Option(_)
+            | NamedFieldKind::RequiredOption(_) => {
+                quote! { self.#name = ::std::option::Option::None; }
+            }
+            NamedFieldKind::VecWithEach(_, _) => quote! { self.#name.clear(); },
+            NamedFieldKind::Skip(_) | NamedFieldKind::Computed(_) => return None,
+        };
+        Some(self.with_setter_attrs(quote! {
+            pub fn #unset_fn_name(&mut self) -> &mut Self {
+                #unset
+                self
+            }
+        }))
+    }
+
+    /// Produce a local binding of this field's final value, used to build the struct
+    ///
+    /// `Option<T>` | `Vec<T>` => `let this_field_name = self.this_field_name.clone();`
+    /// `T` => `let this_field_name = self.this_field_name.clone().ok_or("field not set")?;`
+    /// `required` `Option<T>` =>
+    ///     `let this_field_name = self.this_field_name.clone().ok_or(MissingField { ... })?;`
+    /// `skip` => `let this_field_name: ThisFieldType = Default::default();`
+    /// `computed` => `let this_field_name: ThisFieldType = computed_expr;`
+    ///
+    /// Computed fields must be bound after every other field so the expression can refer to
+    /// them by name.
+    pub fn as_build_binding(&self) -> TokenStream {
+        let field_name = &self.name;
+        let ty = &self.ty;
+        match &self.kind {
+            NamedFieldKind::Normal => {
+                quote! { let #field_name = self.#field_name.clone().ok_or("field not set")?; }
+            }
+            NamedFieldKind::RequiredOption(_) => {
+                let field = field_name.unraw().to_string();
+                quote! {
+                    let #field_name = self.#field_name.clone().ok_or(
+                        ::derive_builder::BuildError::MissingField { field: #field },
+                    )?;
+                }
+            }
+            NamedFieldKind::Option(_) | NamedFieldKind::VecWithEach(_, _) => {
+                quote! { let #field_name = self.#field_name.clone(); }
+            }
+            NamedFieldKind::Skip(None) => {
+                quote! { let #field_name: #ty = ::std::default::Default::default(); }
+            }
+            NamedFieldKind::Skip(Some(expr)) | NamedFieldKind::Computed(expr) => {
+                quote! { let #field_name: #ty = #expr; }
+            }
+        }
+    }
+
+    /// Produce this field in struct literal shorthand, `this_field_name`, after its binding
+    ///
+    /// Initializing a deprecated field is not a use the caller can do anything about, so the
+    /// lint is silenced here.
+    pub fn as_field_shorthand(&self) -> TokenStream {
+        let name = &self.name;
+        let allow = self
+            .deprecated
+            .as_ref()
+            .map(|_| quote!(#[allow(deprecated)]));
+        quote! {
+            #allow
+            #name
+        }
+    }
+
+    /// Produce a `bool` expression telling whether this field has been set on the builder
+    ///
+    /// `T` | `Option<T>` => `self.this_field_name.is_some()`
+    /// `Vec<T>` => `!self.this_field_name.is_empty()`
+    ///
+    /// Skipped and computed fields are never set through the builder.
+    pub fn as_is_set(&self) -> Option<TokenStream> {
+        let name = &self.name;
+        let is_set = match &self.kind {
+            NamedFieldKind::Normal
+            | NamedFieldKind::Option(_)
+            | NamedFieldKind::RequiredOption(_) => quote! { self.#name.is_some() },
+            NamedFieldKind::VecWithEach(_, _) => quote! { !self.#name.is_empty() },
+            NamedFieldKind::Skip(_) | NamedFieldKind::Computed(_) => return None,
+        };
+        Some(is_set)
+    }
+
+    /// Produce a statement adding this field to the `debug` builder of a `Debug` impl
+    ///
+    /// Unset fields show as `<unset>`, and set fields with `#[builder(redact)]` as `***`.
+    /// `Vec<T>` fields are always set, and show the items pushed so far.
+    ///
+    /// Skipped and computed fields are not stored in the builder, so they are left out.
+    pub fn as_debug_field(&self) -> Option<TokenStream> {
+        let name = &self.name;
+        let label = name.unraw().to_string();
+        let value = match (&self.kind, &self.redact) {
+            (
+                NamedFieldKind::Normal
+                | NamedFieldKind::Option(_)
+                | NamedFieldKind::RequiredOption(_),
+                redact,
+            ) => {
+                let set = match redact {
+                    Some(_) => {
+                        quote!(::std::option::Option::Some(_) => &::derive_builder::Redacted)
+                    }
+                    None => quote!(::std::option::Option::Some(value) => value),
+                };
+                quote! {
+                    match &self.#name {
+                        #set,
+                        ::std::option::Option::None => &::derive_builder::Unset,
+                    }
+                }
+            }
+            (NamedFieldKind::VecWithEach(_, _), Some(_)) => {
+                quote!(&::derive_builder::Redacted)
+            }
+            (NamedFieldKind::VecWithEach(_, _), None) => quote!(&self.#name),
+            (NamedFieldKind::Skip(_) | NamedFieldKind::Computed(_), _) => return None,
+        };
+
+        Some(quote! {
+            debug.field(#label, #value);
+        })
+    }
+
+    /// Produce a statement returning `false` from `is_complete()` if this field must be set but
+    /// is not
+    pub fn as_complete_check(&self) -> Option<TokenStream> {
+        let name = &self.name;
+        match &self.kind {
+            NamedFieldKind::Normal | NamedFieldKind::RequiredOption(_) => Some(quote! {
+                if self.#name.is_none() {
+                    return false;
+                }
+            }),
+            NamedFieldKind::Option(_)
+            | NamedFieldKind::VecWithEach(_, _)
+            | NamedFieldKind::Skip(_)
+            | NamedFieldKind::Computed(_) => None,
+        }
+    }
+
+    /// Check that this field can be stored in a `#[builder(const)]` builder
+    pub fn validate_const(&self) -> syn::Result<()> {
+        match &self.kind {
+            NamedFieldKind::VecWithEach(_, each_fn_name) => Err(syn::Error::new(
+                each_fn_name.span(),
+                "`each` is not supported by `builder(const)`",
+            )),
+            NamedFieldKind::Skip(None) => Err(syn::Error::new(
+                self.name.span(),
+                "skipped fields of a `builder(const)` struct need `builder(default = \"...\")`",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Produce a compile-time assertion that the type stored in the builder for this field is
+    /// `Copy`, as required by `#[builder(const)]`
+    ///
+    /// ```ignore
+    /// ::derive_builder::assert_const_field::<ThisFieldType>();
+    /// ```
+    ///
+    /// Every `const fn` that could drop this field starts with the assertion. rustc does not
+    /// const-check a body that already failed to type check, so a non-`Copy` field is reported
+    /// once, here, instead of also as a destructor that cannot run at compile time.
+    pub fn as_const_field_assertion(&self) -> Option<TokenStream> {
+        let ty = match &self.kind {
+            NamedFieldKind::Normal | NamedFieldKind::RequiredOption(_) => &self.ty,
+            NamedFieldKind::Option(inner_ty) => inner_ty,
+            NamedFieldKind::VecWithEach(_, _)
+            | NamedFieldKind::Skip(_)
+            | NamedFieldKind::Computed(_) => return None,
+        };
+        Some(quote_spanned! {ty.span()=>
+            ::derive_builder::assert_const_field::<#ty>();
+        })
+    }
+
+    /// Produce an owned `const fn` setter for this field on a `#[builder(const)]` builder
+    ///
+    /// ```ignore
+    /// pub const fn this_field_name(mut self, this_field_name: ThisFieldType) -> Self {
+    ///     self.this_field_name = Some(this_field_name);
+    ///     self
+    /// }
+    /// ```
+    pub fn as_const_setter_fn(&self) -> Option<TokenStream> {
+        let name = &self.name;
+        let ty = match &self.kind {
+            NamedFieldKind::Normal | NamedFieldKind::RequiredOption(_) => &self.ty,
+            NamedFieldKind::Option(inner_ty) => inner_ty,
+            NamedFieldKind::VecWithEach(_, _)
+            | NamedFieldKind::Skip(_)
+            | NamedFieldKind::Computed(_) => return None,
+        };
+
+        let assertion = self.as_const_field_assertion();
+        Some(self.with_setter_attrs(quote! {
+            pub const fn #name(mut self, #name: #ty) -> Self {
+                #assertion
+                self.#name = ::std::option::Option::Some(#name);
+                self
+            }
+        }))
+    }
+
+    /// Produce an owned `const fn` returning this field to its initial, unset state, on a
+    /// `#[builder(const)]` builder
+    pub fn as_const_unset_fn(&self) -> Option<TokenStream> {
+        let name = &self.name;
+        let unset_fn_name = self.unset_fn_name()?;
+
+        match &self.kind {
+            NamedFieldKind::Normal
+            | NamedFieldKind::Option(_)
+            | NamedFieldKind::RequiredOption(_) => {}
+            NamedFieldKind::VecWithEach(_, _)
+            | NamedFieldKind::Skip(_)
+            | NamedFieldKind::Computed(_) => return None,
+        }
+        let assertion = self.as_const_field_assertion();
+        Some(self.with_setter_attrs(quote! {
+            pub const fn #unset_fn_name(mut self) -> Self {
+                #assertion
+                self.#name = ::std::option::Option::None;
+                self
+            }
+        }))
+    }
+
+    /// Produce a local binding of this field's final value in a `const fn build()`
+    ///
+    /// Like [`Self::as_build_binding`], but moves out of the builder instead of cloning, and
+    /// panics if a required field is not set.
+    pub fn as_const_build_binding(&self) -> TokenStream {
+        let field_name = &self.name;
+        let ty = &self.ty;
+        match &self.kind {
+            NamedFieldKind::Normal | NamedFieldKind::RequiredOption(_) => {
+                let message = format!("field `{}` not set", field_name.unraw());
+                quote! {
+                    let #field_name = match self.#field_name {
+                        ::std::option::Option::Some(#field_name) => #field_name,
+                        ::std::option::Option::None => ::std::panic!(#message),
+                    };
+                }
+            }
+            NamedFieldKind::Option(_) | NamedFieldKind::VecWithEach(_, _) => {
+                quote! { let #field_name = self.#field_name; }
+            }
+            NamedFieldKind::Skip(None) => {
+                quote! { let #field_name: #ty = ::std::default::Default::default(); }
+            }
+            NamedFieldKind::Skip(Some(expr)) | NamedFieldKind::Computed(expr) => {
+                quote! { let #field_name: #ty = #expr; }
+            }
+        }
+    }
+
+    /// Produce a statement storing `value`, a `String` read from `source`, in this field
+    ///
+    /// ```ignore
+    /// self.this_field_name = Some(::derive_builder::parse_field::<ThisFieldType>(source, &value)?);
+    /// ```
+    ///
+    /// `Vec<T>` fields with `each` push the parsed value instead, after splitting it on `separator`
+    /// if one is given. The parse is spanned to the field's type, so a type that is not `FromStr`
+    /// is reported there.
+    fn as_value_loader(&self, source: &str, separator: Option<&str>) -> Option<TokenStream> {
+        let name = &self.name;
+        let ty = self.inner_ty();
+        let parse = |value: TokenStream| {
+            quote_spanned! {ty.span()=>
+                ::derive_builder::parse_field::<#ty>(#source, &#value)?
+            }
+        };
+
+        let apply = match &self.kind {
+            NamedFieldKind::Normal | NamedFieldKind::Option(_) => {
+                let value = parse(quote!(value));
+                quote! { self.#name = ::std::option::Option::Some(#value); }
+            }
+            NamedFieldKind::RequiredOption(_) => {
+                let value = parse(quote!(value));
+                quote! {
+                    self.#name = ::std::option::Option::Some(::std::option::Option::Some(#value));
+                }
+            }
+            NamedFieldKind::VecWithEach(_, _) => match separator {
+                Some(separator) => {
+                    let item = parse(quote!(item));
+                    quote! {
+                        if !value.is_empty() {
+                            for item in value.split(#separator) {
+                                self.#name.push(#item);
+                            }
+                        }
+                    }
+                }
+                None => {
+                    let value = parse(quote!(value));
+                    quote! { self.#name.push(#value); }
+                }
+            },
+            NamedFieldKind::Skip(_) | NamedFieldKind::Computed(_) => return None,
+        };
+        Some(apply)
+    }
+
+    /// Produce a statement loading this field from the environment variable
+    /// `{env_prefix}{FIELD_NAME}`, if it is present
+    ///
+    /// ```ignore
+    /// match ::std::env::var("APP_THIS_FIELD_NAME") {
+    ///     Ok(value) => {
+    ///         self.this_field_name = Some(parse_field::<ThisFieldType>("APP_THIS_FIELD_NAME", &value)?);
+    ///     }
+    ///     Err(VarError::NotPresent) => {}
+    ///     Err(err) => return Err(err),
+    /// }
+    /// ```
+    ///
+    /// `Vec<T>` fields with `each` split the value on `env_separator` and push each element.
+    pub fn as_env_loader(
+        &self,
+        env_prefix: &syn::LitStr,
+        env_separator: &str,
+    ) -> Option<TokenStream> {
+        let var = format!(
+            "{}{}",
+            env_prefix.value(),
+            self.name.unraw().to_string().to_uppercase()
+        );
+        let apply = self.as_value_loader(&var, Some(env_separator))?;
+
+        Some(quote! {
+            match ::std::env::var(#var) {
+                ::std::result::Result::Ok(value) => {
+                    #apply
+                }
+                ::std::result::Result::Err(::std::env::VarError::NotPresent) => {}
+                ::std::result::Result::Err(err) => {
+                    return ::std::result::Result::Err(
+                        ::std::format!("invalid value for `{}`: {}", #var, err).into(),
+                    );
+                }
+            }
+        })
+    }
+
+    /// Produce a match arm loading this field from the flag `--this-field-name`
+    ///
+    /// ```ignore
+    /// "--this-field-name" => {
+    ///     self.this_field_name = Some(parse_field::<ThisFieldType>("--this-field-name", &value)?);
+    /// }
+    /// ```
+    ///
+    /// `Vec<T>` fields with `each` push one element each time the flag is given.
+    pub fn as_args_loader(&self) -> Option<TokenStream> {
+        let flag = format!("--{}", self.name.unraw().to_string().replace('_', "-"));
+        let apply = self.as_value_loader(&flag, None)?;
+
+        Some(quote! {
+            #flag => {
+                #apply
+            }
+        })
+    }
+}
+
+impl TryFrom<&syn::Field> for NamedFieldData {
+    type Error = syn::Error;
+
+    fn try_from(field: &syn::Field) -> Result<Self, Self::Error> {
+        Self::new(field.ident.clone().unwrap(), &field.ty, &field.attrs)
+    }
+}
+
+impl NamedFieldData {
+    /// Collect the data for a struct field or function parameter named `name`, from its type and
+    /// its `#[builder(...)]` attributes
+    pub fn new(name: syn::Ident, ty: &syn::Type, attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let options = options::extract_from_attrs(attrs)?;
+
+        if let Some(required) = &options.required
+            && util::extract_inner_ty(ty, "Option").is_none()
+        {
+            return Err(syn::Error::new(
+                required.span(),
+                "`required` only applies to `Option` fields",
+            ));
+        }
+
+        let kind = if let Some(computed) = options.computed {
+            NamedFieldKind::Computed(computed)
+        } else if options.skip.is_some() {
+            NamedFieldKind::Skip(options.default)
+        } else if let Some(each_fn_name) = options.each
+            && let Some(inner_ty) = util::extract_inner_ty(ty, "Vec")
+        {
+            NamedFieldKind::VecWithEach(inner_ty, each_fn_name)
+        } else if let Some(inner_ty) = util::extract_inner_ty(ty, "Option") {
+            if options.required.is_some() {
+                NamedFieldKind::RequiredOption(inner_ty)
+            } else {
+                NamedFieldKind::Option(inner_ty)
+            }
+        } else {
+            NamedFieldKind::Normal
+        };
+
+        Ok(Self {
+            name,
+            ty: ty.clone(),
+            kind,
+            requires: options.requires,
+            conflicts_with: options.conflicts_with,
+            deprecated: attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("deprecated"))
+                .cloned(),
+            redact: options.redact,
+        })
+    }
+}
@@ -0,0 +1,273 @@
+use syn::parse::Parse;
+use syn::spanned::Spanned;
+
+/// Struct-level `#[builder(...)]` options
+#[derive(Debug, Default)]
+pub struct BuilderOptions {
+    /// `#[builder(env_prefix = "...")]`, generates `from_env` and `apply_env`
+    pub env_prefix: Option<syn::LitStr>,
+    /// `#[builder(env_separator = "...")]`, splits variables for `each` fields
+    pub env_separator: Option<syn::LitStr>,
+    /// `#[builder(args)]`, generates `from_args` and `apply_args`
+    pub args: Option<syn::Path>,
+    /// `#[builder(const)]`, generates `const fn` owned setters and `build()`
+    pub const_fn: Option<syn::Path>,
+    /// `#[builder(group(...))]`, constraints on how many of a set of fields are set
+    pub groups: Vec<Group>,
+    /// `#[builder(debug)]`, implements `Debug` for the builder
+    pub debug: Option<syn::Path>,
+}
+
+/// `#[builder(group(name = "...", exactly_one = [...]))]`
+#[derive(Debug)]
+pub struct Group {
+    pub name: syn::LitStr,
+    pub rule: GroupRule,
+    pub fields: Vec<syn::Ident>,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::enum_variant_names)] // named after `exactly_one`, `at_least_one` and `at_most_one`
+pub enum GroupRule {
+    ExactlyOne,
+    AtLeastOne,
+    AtMostOne,
+}
+
+impl BuilderOptions {
+    /// Methods of the builder that are not generated for a field
+    pub fn method_names(&self) -> Vec<&'static str> {
+        let mut names = vec!["build", "is_complete", "reset"];
+        if self.env_prefix.is_some() {
+            names.extend(["from_env", "apply_env"]);
+        }
+        if self.args.is_some() {
+            names.extend(["from_args", "apply_args"]);
+        }
+        names
+    }
+
+    /// Separator for `each` fields loaded from the environment, `,` if not given
+    pub fn env_separator(&self) -> String {
+        self.env_separator
+            .as_ref()
+            .map_or_else(|| ",".to_owned(), syn::LitStr::value)
+    }
+}
+
+/// Extract struct-level options from derive input attributes
+pub fn extract_from_derive_input(input: &syn::DeriveInput) -> syn::Result<BuilderOptions> {
+    let mut options = BuilderOptions::default();
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("const") {
+                options.const_fn = Some(meta.path.clone());
+            } else if meta.path.is_ident("args") {
+                options.args = Some(meta.path.clone());
+            } else if meta.path.is_ident("env_prefix") {
+                options.env_prefix = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("env_separator") {
+                options.env_separator = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("group") {
+                options.groups.push(parse_group(&meta)?);
+            } else if meta.path.is_ident("debug") {
+                options.debug = Some(meta.path.clone());
+            } else {
+                return Err(meta.error(
+                    "expected `builder(args)`, `builder(const)`, `builder(debug)`, `builder(env_prefix = \"...\")`, `builder(env_separator = \"...\")` or `builder(group(...))`",
+                ));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    if let Some(env_separator) = &options.env_separator {
+        if options.env_prefix.is_none() {
+            return Err(syn::Error::new(
+                env_separator.span(),
+                "`env_separator` requires `env_prefix`",
+            ));
+        }
+        if env_separator.value().is_empty() {
+            return Err(syn::Error::new(
+                env_separator.span(),
+                "`env_separator` must not be empty",
+            ));
+        }
+    }
+
+    if let Some(const_fn) = &options.const_fn
+        && (options.env_prefix.is_some() || options.args.is_some())
+    {
+        return Err(syn::Error::new(
+            const_fn.span(),
+            "`const` cannot be combined with `env_prefix` or `args`",
+        ));
+    }
+
+    Ok(options)
+}
+
+fn parse_group(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Group> {
+    let mut name: Option<syn::LitStr> = None;
+    let mut rule_and_fields = None;
+
+    meta.parse_nested_meta(|meta| {
+        let rule = if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            return Ok(());
+        } else if meta.path.is_ident("exactly_one") {
+            GroupRule::ExactlyOne
+        } else if meta.path.is_ident("at_least_one") {
+            GroupRule::AtLeastOne
+        } else if meta.path.is_ident("at_most_one") {
+            GroupRule::AtMostOne
+        } else {
+            return Err(meta.error(
+                "expected `name = \"...\"`, `exactly_one = [...]`, `at_least_one = [...]` or `at_most_one = [...]`",
+            ));
+        };
+
+        if rule_and_fields.is_some() {
+            return Err(meta.error("a group can only have one rule"));
+        }
+        rule_and_fields = Some((rule, parse_field_list(&meta)?));
+
+        Ok(())
+    })?;
+
+    let Some(name) = name else {
+        return Err(meta.error("expected `name = \"...\"` in `builder(group(...))`"));
+    };
+    let Some((rule, fields)) = rule_and_fields else {
+        return Err(syn::Error::new(
+            name.span(),
+            "expected `exactly_one = [...]`, `at_least_one = [...]` or `at_most_one = [...]`",
+        ));
+    };
+
+    Ok(Group { name, rule, fields })
+}
+
+/// Parse `= field` or `= [field, ...]`
+fn parse_field_list(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Vec<syn::Ident>> {
+    let value = meta.value()?;
+    if !value.peek(syn::token::Bracket) {
+        return Ok(vec![value.parse()?]);
+    }
+
+    let content;
+    syn::bracketed!(content in value);
+    let fields = content.parse_terminated(syn::Ident::parse, syn::Token![,])?;
+    Ok(fields.into_iter().collect())
+}
+
+/// Field-level `#[builder(...)]` options
+#[derive(Debug, Default)]
+pub struct FieldOptions {
+    /// `#[builder(each = "...")]`, the name of the one-at-a-time setter
+    pub each: Option<syn::Ident>,
+    /// `#[builder(skip)]`, the field is not stored in the builder
+    pub skip: Option<syn::Path>,
+    /// `#[builder(default = "...")]`, the value of a skipped field
+    pub default: Option<syn::Expr>,
+    /// `#[builder(computed = "...")]`, evaluated in `build()` after all other fields
+    pub computed: Option<syn::Expr>,
+    /// `#[builder(required)]`, an `Option` field that must be set, even to `None`
+    pub required: Option<syn::Path>,
+    /// `#[builder(requires = ...)]`, fields that must be set if this one is
+    pub requires: Vec<syn::Ident>,
+    /// `#[builder(conflicts_with = ...)]`, fields that must not be set if this one is
+    pub conflicts_with: Vec<syn::Ident>,
+    /// `#[builder(redact)]`, the value is hidden from the builder's `Debug` output
+    pub redact: Option<syn::Path>,
+}
+
+/// Extract field-level options from the attributes of a field or function parameter
+pub fn extract_from_attrs(attrs: &[syn::Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+
+    for attr in attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("each") {
+                let lit = meta.value()?.parse::<syn::LitStr>()?;
+                options.each = Some(syn::Ident::new(&lit.value(), lit.span()));
+            } else if meta.path.is_ident("skip") {
+                options.skip = Some(meta.path.clone());
+            } else if meta.path.is_ident("default") {
+                let lit = meta.value()?.parse::<syn::LitStr>()?;
+                options.default = Some(lit.parse()?);
+            } else if meta.path.is_ident("required") {
+                options.required = Some(meta.path.clone());
+            } else if meta.path.is_ident("computed") {
+                let lit = meta.value()?.parse::<syn::LitStr>()?;
+                options.computed = Some(lit.parse()?);
+            } else if meta.path.is_ident("requires") {
+                options.requires.extend(parse_field_list(&meta)?);
+            } else if meta.path.is_ident("conflicts_with") {
+                options.conflicts_with.extend(parse_field_list(&meta)?);
+            } else if meta.path.is_ident("redact") {
+                options.redact = Some(meta.path.clone());
+            } else {
+                return Err(meta.error(
+                    "expected `builder(each = \"...\")`, `builder(skip)`, `builder(default = \"...\")`, `builder(computed = \"...\")`, `builder(required)`, `builder(requires = ...)`, `builder(conflicts_with = ...)` or `builder(redact)`",
+                ));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    if let Some(default) = &options.default
+        && options.skip.is_none()
+    {
+        return Err(syn::Error::new(
+            default.span(),
+            "`default` requires `builder(skip)`",
+        ));
+    }
+    if let Some(computed) = &options.computed
+        && (options.skip.is_some() || options.each.is_some())
+    {
+        return Err(syn::Error::new(
+            computed.span(),
+            "`computed` cannot be combined with `skip` or `each`",
+        ));
+    }
+    if let Some(required) = &options.required
+        && (options.skip.is_some() || options.computed.is_some())
+    {
+        return Err(syn::Error::new(
+            required.span(),
+            "`required` cannot be combined with `skip` or `computed`",
+        ));
+    }
+    if let Some(skip) = &options.skip
+        && options.each.is_some()
+    {
+        return Err(syn::Error::new(
+            skip.span(),
+            "`skip` cannot be combined with `each`",
+        ));
+    }
+    if let Some(redact) = &options.redact
+        && (options.skip.is_some() || options.computed.is_some())
+    {
+        return Err(syn::Error::new(
+            redact.span(),
+            "`redact` cannot be combined with `skip` or `computed`",
+        ));
+    }
+
+    Ok(options)
+}
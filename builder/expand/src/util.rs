@@ -0,0 +1,120 @@
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+
+/// If the given type is `outer_ty<T>`, returns `T`
+pub fn extract_inner_ty(ty: &syn::Type, outer_ty: &str) -> Option<syn::Type> {
+    let syn::Type::Path(syn::TypePath { path, .. }) = ty else {
+        return None;
+    };
+
+    if path.segments.len() != 1 {
+        return None;
+    }
+    let first_segment = path.segments.first()?;
+
+    if first_segment.ident != outer_ty {
+        return None;
+    }
+
+    let syn::PathArguments::AngleBracketed(path_args) = &first_segment.arguments else {
+        return None;
+    };
+
+    if path_args.args.len() != 1 {
+        return None;
+    }
+    let syn::GenericArgument::Type(inner_ty) = path_args.args.first()? else {
+        return None;
+    };
+
+    Some(inner_ty.clone())
+}
+
+/// Convert a `snake_case` identifier to `PascalCase`
+pub fn to_pascal_case(ident: &str) -> String {
+    ident
+        .trim_start_matches("r#")
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Replace every `Self` in the given type with `self_ty`, for types moved out of an impl block
+pub fn replace_self_ty(ty: &syn::Type, self_ty: &syn::TypePath) -> syn::Type {
+    fn replace(tokens: TokenStream, self_ty: &syn::TypePath) -> TokenStream {
+        tokens
+            .into_iter()
+            .flat_map(|token| match token {
+                TokenTree::Ident(ident) if ident == "Self" => self_ty.to_token_stream(),
+                TokenTree::Group(group) => {
+                    let mut replaced =
+                        Group::new(group.delimiter(), replace(group.stream(), self_ty));
+                    replaced.set_span(group.span());
+                    TokenTree::Group(replaced).into()
+                }
+                token => token.into(),
+            })
+            .collect()
+    }
+
+    syn::parse2(replace(ty.to_token_stream(), self_ty)).unwrap()
+}
+
+/// Find the first `Self` in the given tokens
+pub fn find_self_ty(tokens: TokenStream) -> Option<Span> {
+    tokens.into_iter().find_map(|token| match token {
+        TokenTree::Ident(ident) if ident == "Self" => Some(ident.span()),
+        TokenTree::Group(group) => find_self_ty(group.stream()),
+        _ => None,
+    })
+}
+
+/// Name every elided lifetime in the given type `lifetime`, for types stored in a builder
+///
+/// Lifetimes elided inside `fn(...)` pointers and `Fn(...)` bounds are higher-ranked, so they are
+/// left alone. Returns whether any lifetime was named.
+pub fn name_elided_lifetimes(ty: &mut syn::Type, lifetime: &syn::Lifetime) -> bool {
+    struct NameElided<'a> {
+        lifetime: &'a syn::Lifetime,
+        named: bool,
+    }
+
+    impl VisitMut for NameElided<'_> {
+        fn visit_type_reference_mut(&mut self, reference: &mut syn::TypeReference) {
+            if reference.lifetime.is_none() {
+                reference.lifetime = Some(self.lifetime.clone());
+                self.named = true;
+            }
+            visit_mut::visit_type_reference_mut(self, reference);
+        }
+
+        fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+            if lifetime.ident == "_" {
+                *lifetime = self.lifetime.clone();
+                self.named = true;
+            }
+        }
+
+        fn visit_type_bare_fn_mut(&mut self, _: &mut syn::TypeBareFn) {}
+
+        fn visit_parenthesized_generic_arguments_mut(
+            &mut self,
+            _: &mut syn::ParenthesizedGenericArguments,
+        ) {
+        }
+    }
+
+    let mut visitor = NameElided {
+        lifetime,
+        named: false,
+    };
+    visitor.visit_type_mut(ty);
+    visitor.named
+}
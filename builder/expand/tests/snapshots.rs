@@ -0,0 +1,256 @@
+// Snapshots of the code generated by `#[derive(Builder)]`, so that changes to
+// the expansion show up in review. After an intentional change, regenerate the
+// snapshots with `cargo insta review` or `INSTA_UPDATE=always cargo test`.
+
+use derive_builder_expand::{derive_builder, fn_builder};
+use proc_macro2::TokenStream;
+use syn::parse_quote;
+
+fn expand(input: syn::DeriveInput) -> String {
+    let tokens = derive_builder(input).unwrap();
+    prettyplease::unparse(&syn::parse2(tokens).unwrap())
+}
+
+fn expand_fn(input: syn::Item) -> String {
+    let tokens = fn_builder(TokenStream::new(), input).unwrap();
+    prettyplease::unparse(&syn::parse2(tokens).unwrap())
+}
+
+#[test]
+fn normal_field() {
+    let expanded = expand(parse_quote! {
+        pub struct Command {
+            executable: String,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn option_field() {
+    let expanded = expand(parse_quote! {
+        pub struct Command {
+            current_dir: Option<String>,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn required_option_field() {
+    let expanded = expand(parse_quote! {
+        pub struct Request {
+            #[builder(required)]
+            body: Option<String>,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn vec_field() {
+    let expanded = expand(parse_quote! {
+        pub struct Command {
+            args: Vec<String>,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn vec_with_each_field() {
+    let expanded = expand(parse_quote! {
+        pub struct Command {
+            #[builder(each = "arg")]
+            args: Vec<String>,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn skip_field() {
+    let expanded = expand(parse_quote! {
+        pub struct Command {
+            #[builder(skip)]
+            runs: u32,
+            #[builder(skip, default = "vec![\"PATH\".to_owned()]")]
+            inherited_env: Vec<String>,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn computed_field() {
+    let expanded = expand(parse_quote! {
+        pub struct Command {
+            #[builder(computed = "args.len()")]
+            arg_count: usize,
+            #[builder(each = "arg")]
+            args: Vec<String>,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn deprecated_field() {
+    let expanded = expand(parse_quote! {
+        pub struct Config {
+            name: String,
+            #[deprecated]
+            label: Option<String>,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn debug_redact() {
+    let expanded = expand(parse_quote! {
+        #[builder(debug)]
+        pub struct Login {
+            user: String,
+            #[builder(redact)]
+            password: String,
+            #[builder(each = "scope")]
+            scopes: Vec<String>,
+            #[builder(skip)]
+            attempts: u32,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn const_builder() {
+    let expanded = expand(parse_quote! {
+        #[builder(const)]
+        pub struct Limits {
+            max_connections: u32,
+            timeout_ms: Option<u64>,
+            #[builder(required)]
+            retries: Option<u8>,
+            #[builder(skip, default = "true")]
+            enabled: bool,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn env_prefix() {
+    let expanded = expand(parse_quote! {
+        #[builder(env_prefix = "APP_", env_separator = ";")]
+        pub struct Config {
+            host: String,
+            log_level: Option<String>,
+            #[builder(each = "feature")]
+            features: Vec<String>,
+            #[builder(skip)]
+            runs: u32,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn args() {
+    let expanded = expand(parse_quote! {
+        #[builder(args)]
+        pub struct Command {
+            executable: String,
+            retry_count: Option<u8>,
+            #[builder(each = "arg")]
+            args: Vec<String>,
+            #[builder(computed = "args.len()")]
+            arg_count: usize,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn constraints() {
+    let expanded = expand(parse_quote! {
+        #[builder(group(name = "source", exactly_one = [path, urls]))]
+        pub struct Job {
+            path: Option<String>,
+            #[builder(each = "url")]
+            urls: Vec<String>,
+            #[builder(requires = cert, conflicts_with = urls)]
+            tls: Option<bool>,
+            cert: Option<String>,
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn fn_builder_free() {
+    let expanded = expand_fn(parse_quote! {
+        pub async fn connect(
+            host: String,
+            port: Option<u16>,
+            #[builder(each = "header")] headers: Vec<String>,
+        ) -> Connection {
+            todo!()
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn fn_builder_methods() {
+    let expanded = expand_fn(parse_quote! {
+        impl Client {
+            #[fn_builder]
+            pub fn new(name: String) -> Self {
+                todo!()
+            }
+
+            #[fn_builder]
+            pub fn send(&mut self, line: String, #[builder(skip)] retries: u8) {
+                todo!()
+            }
+
+            pub fn unbuilt(&self) {}
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn fn_builder_borrowed() {
+    let expanded = expand_fn(parse_quote! {
+        pub fn find(haystack: &str, needles: &[&'static str], limit: Option<&'_ u32>) -> &str {
+            todo!()
+        }
+    });
+    insta::assert_snapshot!(expanded);
+}
+
+#[test]
+fn invalid_attribute() {
+    let input: syn::DeriveInput = parse_quote! {
+        pub struct Command {
+            #[builder(eac = "arg")]
+            args: Vec<String>,
+        }
+    };
+    let err = derive_builder(input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expected `builder(each = \"...\")`, `builder(skip)`, `builder(default = \"...\")`, `builder(computed = \"...\")`, `builder(required)`, `builder(requires = ...)`, `builder(conflicts_with = ...)` or `builder(redact)`",
+    );
+}
+
+#[test]
+fn fn_builder_call_arg() {
+    let input: syn::Item = parse_quote! {
+        fn schedule(at: u64, call: String) {}
+    };
+    let err = fn_builder(TokenStream::new(), input).unwrap_err();
+    assert_eq!(err.to_string(), "`call` is already a method of the builder");
+}
@@ -0,0 +1,23 @@
+use syn::parse_macro_input;
+
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    derive_builder_expand::derive_builder(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_attribute]
+pub fn fn_builder(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let args = args.into();
+    let input = parse_macro_input!(input as syn::Item);
+
+    derive_builder_expand::fn_builder(args, input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
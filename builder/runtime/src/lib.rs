@@ -0,0 +1,173 @@
+// The traits and helpers the code generated by `#[derive(Builder)]` relies on.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A type with a builder generated by `#[derive(Builder)]`
+pub trait Buildable: Sized {
+    /// The generated builder, e.g. `CommandBuilder` for `Command`
+    type Builder: Builder<Target = Self>;
+
+    /// Produce a builder with every field unset
+    fn builder() -> Self::Builder;
+}
+
+/// A builder generated by `#[derive(Builder)]`
+pub trait Builder {
+    /// The type produced by this builder
+    type Target;
+    /// The error returned when the builder is incomplete
+    type Error;
+
+    /// Produce the target type from the fields set so far
+    fn build(&self) -> Result<Self::Target, Self::Error>;
+}
+
+/// The reasons a generated `build()` can fail
+///
+/// `build()` returns these boxed as `Box<dyn Error>`, so callers that need to tell them apart can
+/// `downcast_ref::<BuildError>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// A `builder(required)` field was set to neither `Some` nor `None`
+    MissingField { field: &'static str },
+    /// `builder(group(exactly_one = [...]))` with `set` of its fields set
+    ExactlyOne {
+        group: &'static str,
+        fields: &'static [&'static str],
+        set: usize,
+    },
+    /// `builder(group(at_least_one = [...]))` with none of its fields set
+    AtLeastOne {
+        group: &'static str,
+        fields: &'static [&'static str],
+    },
+    /// `builder(group(at_most_one = [...]))` with `set` of its fields set
+    AtMostOne {
+        group: &'static str,
+        fields: &'static [&'static str],
+        set: usize,
+    },
+    /// `field` is set and marked `builder(requires = ...)`, but `requires` is not set
+    Requires {
+        field: &'static str,
+        requires: &'static str,
+    },
+    /// `field` is set and marked `builder(conflicts_with = ...)`, and so is `conflicts_with`
+    ConflictsWith {
+        field: &'static str,
+        conflicts_with: &'static str,
+    },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingField { field } => write!(f, "field `{field}` not set"),
+            Self::ExactlyOne { group, fields, set } => write!(
+                f,
+                "exactly one of {} must be set for group `{group}`, found {set}",
+                FieldList(fields),
+            ),
+            Self::AtLeastOne { group, fields } => write!(
+                f,
+                "at least one of {} must be set for group `{group}`",
+                FieldList(fields),
+            ),
+            Self::AtMostOne { group, fields, set } => write!(
+                f,
+                "at most one of {} may be set for group `{group}`, found {set}",
+                FieldList(fields),
+            ),
+            Self::Requires { field, requires } => {
+                write!(f, "field `{field}` requires field `{requires}` to be set")
+            }
+            Self::ConflictsWith {
+                field,
+                conflicts_with,
+            } => write!(f, "field `{field}` conflicts with field `{conflicts_with}`"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+struct FieldList<'a>(&'a [&'static str]);
+
+impl fmt::Display for FieldList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, field) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "`{field}`")?;
+        }
+        Ok(())
+    }
+}
+
+/// A field type that can be stored in a `#[builder(const)]` builder
+///
+/// Owned `const fn` setters overwrite the previous value without running its destructor, which
+/// is only possible for `Copy` types.
+#[diagnostic::on_unimplemented(
+    message = "fields of a `#[builder(const)]` struct must be `Copy`, `{Self}` is not",
+    label = "not `Copy`"
+)]
+pub trait ConstField: Copy {}
+
+impl<T: Copy> ConstField for T {}
+
+#[doc(hidden)]
+pub const fn assert_const_field<T: ConstField>() {}
+
+/// A field type that can be loaded by the `from_env` and `from_args` loaders of a builder
+///
+/// Values are parsed with `FromStr`, one element at a time for `Vec` fields with `each`.
+#[diagnostic::on_unimplemented(
+    message = "fields loaded by `env_prefix` or `args` must implement `FromStr` with a `Display` error, `{Self}` does not",
+    label = "not `FromStr`",
+    note = "`Vec` fields are loaded one element at a time when they have `builder(each = \"...\")`"
+)]
+pub trait FromStrField: Sized {
+    /// Parse a value, describing why it is invalid on failure
+    fn parse_field(value: &str) -> Result<Self, String>;
+}
+
+impl<T> FromStrField for T
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    fn parse_field(value: &str) -> Result<Self, String> {
+        value.parse().map_err(|err: T::Err| err.to_string())
+    }
+}
+
+/// Parse the `value` of a field loaded from `source`, an environment variable or a flag
+#[doc(hidden)]
+pub fn parse_field<T: FromStrField>(source: &str, value: &str) -> Result<T, String> {
+    T::parse_field(value).map_err(|err| format!("invalid value for `{source}`: {err}"))
+}
+
+/// Shown by the `Debug` impl of a `#[builder(debug)]` builder for a field that was never set
+#[doc(hidden)]
+pub struct Unset;
+
+impl fmt::Debug for Unset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<unset>")
+    }
+}
+
+/// Shown by the `Debug` impl of a `#[builder(debug)]` builder in place of the value of a
+/// `#[builder(redact)]` field
+#[doc(hidden)]
+pub struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
@@ -1,105 +1,11 @@
-mod named_field;
-mod util;
+// Crates that have the "proc-macro" crate type are only allowed to export
+// procedural macros, so the macros live in `derive_builder_macro` and the
+// items their expansion refers to in `derive_builder_runtime`. This crate
+// re-exports both, so it is the only dependency users need, and the generated
+// code refers to everything through `::derive_builder`.
 
-use named_field::NamedFieldData;
-use proc_macro2::TokenStream;
-use quote::quote;
-use syn::parse_macro_input;
+pub use derive_builder_macro::{Builder, fn_builder};
+pub use derive_builder_runtime::{BuildError, Buildable, Builder, ConstField, FromStrField};
 
-#[proc_macro_derive(Builder, attributes(builder))]
-pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input = parse_macro_input!(input as syn::DeriveInput);
-
-    derive_builder(input)
-        .unwrap_or_else(syn::Error::into_compile_error)
-        .into()
-}
-
-fn derive_builder(input: syn::DeriveInput) -> syn::Result<TokenStream> {
-    let name = &input.ident;
-    let vis = &input.vis;
-
-    let builder_name = syn::Ident::new(&format!("{name}Builder"), name.span());
-
-    let struct_fields = named_field::extract_from_derive_input(&input)?;
-
-    let builder = builder(vis, &builder_name, &struct_fields);
-    let builder_initializer = builder_initializer(name, &builder_name, &struct_fields);
-    let builder_impl = builder_impl(name, &builder_name, &struct_fields);
-
-    Ok(output(builder, builder_initializer, builder_impl))
-}
-
-fn output(
-    builder: TokenStream,
-    builder_initializer: TokenStream,
-    build_impl: TokenStream,
-) -> TokenStream {
-    quote! {
-        #builder
-        #builder_initializer
-        #build_impl
-    }
-}
-
-fn builder(
-    vis: &syn::Visibility,
-    builder_name: &syn::Ident,
-    struct_fields: &[NamedFieldData],
-) -> TokenStream {
-    let builder_fields = struct_fields.iter().map(NamedFieldData::as_optional_field);
-
-    quote! {
-        #vis struct #builder_name {
-            #(#builder_fields),*
-        }
-    }
-}
-
-fn builder_initializer(
-    name: &syn::Ident,
-    builder_name: &syn::Ident,
-    struct_fields: &[NamedFieldData],
-) -> TokenStream {
-    let initializers = struct_fields
-        .iter()
-        .map(NamedFieldData::as_field_initializer);
-
-    quote! {
-        impl #name {
-            pub fn builder() -> #builder_name {
-                #builder_name {
-                    #(#initializers),*
-                }
-            }
-        }
-    }
-}
-
-fn builder_impl(
-    name: &syn::Ident,
-    builder_name: &syn::Ident,
-    struct_fields: &[NamedFieldData],
-) -> TokenStream {
-    let setters = struct_fields.iter().map(NamedFieldData::as_setter_fn);
-    let build_fn = build_fn(name, struct_fields);
-
-    quote! {
-        impl #builder_name {
-            #build_fn
-            #(#setters)*
-        }
-    }
-}
-
-fn build_fn(name: &syn::Ident, struct_fields: &[NamedFieldData]) -> TokenStream {
-    let fields = struct_fields.iter().map(NamedFieldData::as_unwrapped_field);
-
-    quote! {
-        pub fn build(&mut self) -> ::std::result::Result<#name, ::std::boxed::Box<dyn ::std::error::Error>> {
-            ::std::result::Result::Ok(#name {
-                #(#fields),*
-            })
-        }
-    }
-}
+#[doc(hidden)]
+pub use derive_builder_runtime::{Redacted, Unset, assert_const_field, parse_field};
@@ -0,0 +1,32 @@
+// Every generated builder implements `derive_builder::Builder`, and
+// every struct deriving `Builder` implements `derive_builder::Buildable`,
+// so generic code can produce and finish builders without naming their types.
+
+use derive_builder::{Buildable, Builder};
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+pub struct Empty {}
+
+fn build_unset<T: Buildable>() -> Result<T, <T::Builder as derive_builder::Builder>::Error> {
+    T::builder().build()
+}
+
+fn main() {
+    assert!(build_unset::<Command>().is_err());
+    assert!(build_unset::<Empty>().is_ok());
+
+    let mut builder = <Command as Buildable>::builder();
+    builder.executable("cargo".to_owned()).arg("build".to_owned());
+    let command = derive_builder::Builder::build(&builder).unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert!(command.current_dir.is_none());
+}
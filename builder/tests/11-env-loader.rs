@@ -0,0 +1,61 @@
+// A struct-level #[builder(env_prefix = "...")] attribute generates
+// `from_env()` and `apply_env(&mut self)` on the builder, which read each field
+// from the environment variable `<PREFIX><FIELD>` and parse it with `FromStr`.
+// Fields with `each` are split on `env_separator`, which defaults to ",".
+// Variables that are not present leave the field untouched.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(env_prefix = "APP_")]
+pub struct Config {
+    host: String,
+    port: u16,
+    #[builder(each = "feature")]
+    features: Vec<String>,
+    log_level: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(env_prefix = "SPLIT_", env_separator = ";")]
+pub struct Split {
+    #[builder(each = "value")]
+    values: Vec<u32>,
+}
+
+fn main() {
+    unsafe {
+        std::env::set_var("APP_HOST", "localhost");
+        std::env::set_var("APP_PORT", "8080");
+        std::env::set_var("APP_FEATURES", "a,b");
+        std::env::set_var("SPLIT_VALUES", "1;2;3");
+    }
+
+    let config = ConfigBuilder::from_env().unwrap().build().unwrap();
+    assert_eq!(config.host, "localhost");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.features, vec!["a", "b"]);
+    assert_eq!(config.log_level, None);
+
+    let config = Config::builder()
+        .feature("c".to_owned())
+        .apply_env()
+        .unwrap()
+        .log_level("debug".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(config.features, vec!["c", "a", "b"]);
+    assert_eq!(config.log_level.as_deref(), Some("debug"));
+
+    let split = SplitBuilder::from_env().unwrap().build().unwrap();
+    assert_eq!(split.values, vec![1, 2, 3]);
+
+    unsafe {
+        std::env::set_var("APP_PORT", "http");
+    }
+    let err = ConfigBuilder::from_env().err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "invalid value for `APP_PORT`: invalid digit found in string",
+    );
+}
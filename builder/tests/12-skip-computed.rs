@@ -0,0 +1,37 @@
+// Fields marked #[builder(skip)] get no builder field or setter and are filled
+// in from `Default::default()`, or from #[builder(default = "...")] if given.
+//
+// Fields marked #[builder(computed = "...")] are also left out of the builder.
+// Their expression is evaluated in `build()` after every other field has been
+// resolved, so it can refer to the other fields by name.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(computed = "args.len()")]
+    arg_count: usize,
+    #[builder(computed = "format!(\"{} {}\", executable, args.join(\" \"))")]
+    command_line: String,
+    #[builder(skip)]
+    runs: u32,
+    #[builder(skip, default = "vec![\"PATH\".to_owned()]")]
+    inherited_env: Vec<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .arg("--release".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.arg_count, 2);
+    assert_eq!(command.command_line, "cargo build --release");
+    assert_eq!(command.runs, 0);
+    assert_eq!(command.inherited_env, vec!["PATH"]);
+}
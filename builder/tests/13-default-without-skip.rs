@@ -0,0 +1,14 @@
+// `default` only applies to skipped fields, which have no setter through which
+// the caller could provide a value.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(default = "\"cargo\".to_owned()")]
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+// An `Option<T>` field marked #[builder(required)] must be decided by the
+// caller: its setter takes the whole `Option<T>`, and `build()` fails if the
+// setter was never called, even though `None` is a valid value.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Request {
+    method: String,
+    #[builder(required)]
+    body: Option<String>,
+    timeout: Option<u32>,
+}
+
+fn main() {
+    let err = Request::builder()
+        .method("GET".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "field `body` not set");
+
+    let request = Request::builder()
+        .method("GET".to_owned())
+        .body(None)
+        .build()
+        .unwrap();
+    assert_eq!(request.body, None);
+    assert_eq!(request.timeout, None);
+
+    let request = Request::builder()
+        .method("POST".to_owned())
+        .body(Some("{}".to_owned()))
+        .build()
+        .unwrap();
+    assert_eq!(request.body.as_deref(), Some("{}"));
+}
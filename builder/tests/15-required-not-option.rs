@@ -0,0 +1,12 @@
+// Fields that are not `Option` are already required, so marking one with
+// #[builder(required)] is most likely a mistake.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Request {
+    #[builder(required)]
+    method: String,
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+// With #[builder(const)] on the struct, `builder()`, the setters and `build()`
+// are all `const fn`, so the builder can be used in `const` and `static`
+// initializers. The setters take and return the builder by value, and `build()`
+// panics instead of returning an error, which fails compilation when it happens
+// during constant evaluation.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(const)]
+pub struct Limits {
+    max_connections: u32,
+    timeout_ms: Option<u64>,
+    #[builder(required)]
+    retries: Option<u8>,
+    #[builder(skip, default = "true")]
+    enabled: bool,
+    #[builder(computed = "max_connections * 2")]
+    max_pending: u32,
+}
+
+const DEFAULT_LIMITS: Limits = Limits::builder()
+    .max_connections(16)
+    .retries(None)
+    .build();
+
+static STRICT_LIMITS: Limits = Limits::builder()
+    .max_connections(4)
+    .timeout_ms(500)
+    .retries(Some(3))
+    .build();
+
+fn main() {
+    assert_eq!(DEFAULT_LIMITS.max_connections, 16);
+    assert_eq!(DEFAULT_LIMITS.timeout_ms, None);
+    assert_eq!(DEFAULT_LIMITS.max_pending, 32);
+    assert!(DEFAULT_LIMITS.enabled);
+    assert_eq!(STRICT_LIMITS.timeout_ms, Some(500));
+    assert_eq!(STRICT_LIMITS.retries, Some(3));
+
+    // outside of constant evaluation, the `Builder` trait still reports missing fields as errors
+    let builder = Limits::builder().max_connections(1);
+    let err = derive_builder::Builder::build(&builder).unwrap_err();
+    assert_eq!(err.to_string(), "field `retries` not set");
+}
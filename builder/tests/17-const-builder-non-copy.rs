@@ -0,0 +1,14 @@
+// Setters of a #[builder(const)] builder overwrite the previous value during
+// constant evaluation, where destructors cannot run, so every field stored in
+// the builder must be `Copy`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Limits {
+    max_connections: u32,
+    name: String,
+}
+
+fn main() {}
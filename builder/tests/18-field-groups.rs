@@ -0,0 +1,78 @@
+// Cross-field rules are declared with #[builder(group(...))] on the struct,
+// and #[builder(requires = ...)] / #[builder(conflicts_with = ...)] on fields.
+// They are checked by `build()`, which fails with a
+// `derive_builder::BuildError` describing the violated rule.
+//
+// A field counts as set once its setter has been called, or for `each` fields
+// once at least one element has been pushed.
+
+use derive_builder::{BuildError, Builder};
+
+#[derive(Builder, Debug)]
+#[builder(group(name = "source", exactly_one = [path, url, inline]))]
+#[builder(group(name = "output", at_most_one = [quiet, verbose]))]
+pub struct Job {
+    path: Option<String>,
+    url: Option<String>,
+    inline: Option<String>,
+    #[builder(requires = cert)]
+    tls: Option<bool>,
+    cert: Option<String>,
+    #[builder(conflicts_with = [url, inline])]
+    watch: Option<bool>,
+    quiet: Option<bool>,
+    verbose: Option<bool>,
+}
+
+fn build_err(builder: &JobBuilder) -> BuildError {
+    let err = builder.build().unwrap_err();
+    err.downcast_ref::<BuildError>().unwrap().clone()
+}
+
+fn main() {
+    let job = Job::builder().path("job.toml".to_owned()).build().unwrap();
+    assert_eq!(job.path.as_deref(), Some("job.toml"));
+
+    assert_eq!(
+        build_err(&Job::builder()),
+        BuildError::ExactlyOne {
+            group: "source",
+            fields: &["path", "url", "inline"],
+            set: 0,
+        },
+    );
+
+    let err = build_err(Job::builder().path(String::new()).url(String::new()));
+    assert_eq!(
+        err.to_string(),
+        "exactly one of `path`, `url`, `inline` must be set for group `source`, found 2",
+    );
+
+    assert_eq!(
+        build_err(Job::builder().path(String::new()).tls(true)),
+        BuildError::Requires {
+            field: "tls",
+            requires: "cert",
+        },
+    );
+    Job::builder()
+        .path(String::new())
+        .tls(true)
+        .cert(String::new())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        build_err(Job::builder().url(String::new()).watch(true)),
+        BuildError::ConflictsWith {
+            field: "watch",
+            conflicts_with: "url",
+        },
+    );
+
+    let err = build_err(Job::builder().path(String::new()).quiet(true).verbose(true));
+    assert_eq!(
+        err.to_string(),
+        "at most one of `quiet`, `verbose` may be set for group `output`, found 2",
+    );
+}
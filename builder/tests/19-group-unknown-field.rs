@@ -0,0 +1,13 @@
+// Groups and `requires` / `conflicts_with` refer to fields by name, so a
+// misspelled field name should be reported where it is written.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(group(name = "source", exactly_one = [path, ulr]))]
+pub struct Job {
+    path: Option<String>,
+    url: Option<String>,
+}
+
+fn main() {}
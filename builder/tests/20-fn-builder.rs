@@ -0,0 +1,72 @@
+// Functions with many parameters get the same treatment as structs with
+// #[derive_builder::fn_builder]: a builder with a setter for every argument,
+// honoring the same #[builder(...)] attributes as struct fields, and a final
+// `.call()` that invokes the function once every required argument is set.
+//
+// For a free function `connect`, the builder is `ConnectBuilder` and is
+// produced by `connect_builder()`. For methods, place #[fn_builder] on the impl
+// block and on each method to build; the builder for `Client::send` is
+// `ClientSendBuilder`, produced by `client.send_builder()`.
+
+use derive_builder::fn_builder;
+
+#[fn_builder]
+fn connect(
+    host: String,
+    port: Option<u16>,
+    #[builder(each = "header")] headers: Vec<String>,
+    #[builder(skip, default = "3")] retries: u8,
+) -> String {
+    format!(
+        "{host}:{} {headers:?} retries={retries}",
+        port.unwrap_or(80),
+    )
+}
+
+pub struct Client {
+    sent: Vec<String>,
+}
+
+#[fn_builder]
+impl Client {
+    #[fn_builder]
+    pub fn new(name: String) -> Self {
+        Client {
+            sent: vec![format!("hello from {name}")],
+        }
+    }
+
+    #[fn_builder]
+    pub fn send(&mut self, #[builder(each = "line")] lines: Vec<String>, urgent: Option<bool>) {
+        let prefix = if urgent.unwrap_or(false) { "!" } else { "" };
+        self.sent.extend(lines.into_iter().map(|line| format!("{prefix}{line}")));
+    }
+
+    #[fn_builder]
+    pub fn count(&self, prefix: String) -> usize {
+        self.sent.iter().filter(|line| line.starts_with(&prefix)).count()
+    }
+}
+
+fn main() {
+    let connection = connect_builder()
+        .host("localhost".to_owned())
+        .header("Accept: */*".to_owned())
+        .call()
+        .unwrap();
+    assert_eq!(connection, "localhost:80 [\"Accept: */*\"] retries=3");
+
+    let err = connect_builder().port(8080).call().unwrap_err();
+    assert_eq!(err.to_string(), "field not set");
+
+    let mut client = Client::new_builder().name("test".to_owned()).call().unwrap();
+    client
+        .send_builder()
+        .line("a".to_owned())
+        .line("b".to_owned())
+        .urgent(true)
+        .call()
+        .unwrap();
+    assert_eq!(client.sent, vec!["hello from test", "!a", "!b"]);
+    assert_eq!(client.count_builder().prefix("!".to_owned()).call().unwrap(), 2);
+}
@@ -0,0 +1,28 @@
+// An attribute macro on a method only sees the method itself, and cannot add
+// the builder struct next to the impl block: structs are not allowed inside
+// impl blocks, and the builder for `Client::send` is `ClientSendBuilder`, a
+// name that needs the type of the impl. Methods are built by placing
+// #[fn_builder] on the enclosing impl block too.
+//
+// Methods are recognized by their receiver and associated functions by a
+// `Self` in their signature. An associated function that never mentions
+// `Self` looks exactly like a free function to the attribute.
+
+use derive_builder::fn_builder;
+
+pub struct Client;
+
+impl Client {
+    #[fn_builder]
+    pub fn send(&self, line: String) {
+        let _ = line;
+    }
+
+    #[fn_builder]
+    pub fn new(name: String) -> Self {
+        let _ = name;
+        Client
+    }
+}
+
+fn main() {}
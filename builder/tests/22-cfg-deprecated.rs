@@ -0,0 +1,71 @@
+// rustc removes the fields whose `#[cfg]` is disabled before the derive sees
+// them, so those fields get no builder storage, setter or build assignment,
+// and setters of deprecated fields are deprecated as well.
+//
+// For the same reason, groups, `requires` and `conflicts_with` cannot name a
+// field that is configured away: to the derive it does not exist. Make the
+// rule itself conditional with `#[cfg_attr(...)]` instead.
+
+#![deny(deprecated)]
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Config {
+    name: String,
+    #[cfg(any())]
+    disabled: NotAType,
+    #[cfg(all())]
+    enabled: u32,
+    #[deprecated = "use `name` instead"]
+    label: Option<String>,
+}
+
+#[derive(Builder, Debug)]
+#[cfg_attr(any(), builder(group(name = "source", exactly_one = [path, url, inline])))]
+#[cfg_attr(not(any()), builder(group(name = "source", exactly_one = [path, inline])))]
+pub struct Job {
+    path: Option<String>,
+    #[cfg(any())]
+    url: Option<String>,
+    inline: Option<String>,
+    #[cfg_attr(any(), builder(requires = cert))]
+    tls: Option<bool>,
+    #[cfg(any())]
+    cert: Option<String>,
+}
+
+#[allow(deprecated)]
+fn with_label(builder: &mut ConfigBuilder) -> &mut ConfigBuilder {
+    builder.label("legacy".to_owned())
+}
+
+fn main() {
+    let mut builder = Config::builder();
+    builder.name("app".to_owned()).enabled(3);
+    with_label(&mut builder);
+    let config = builder.build().unwrap();
+
+    assert_eq!(config.name, "app");
+    assert_eq!(config.enabled, 3);
+    #[allow(deprecated)]
+    let label = config.label;
+    assert_eq!(label.as_deref(), Some("legacy"));
+
+    let job = Job::builder()
+        .path("job.toml".to_owned())
+        .tls(true)
+        .build()
+        .unwrap();
+    assert_eq!(job.path.as_deref(), Some("job.toml"));
+    assert_eq!(job.tls, Some(true));
+    let err = Job::builder()
+        .path("job.toml".to_owned())
+        .inline("[job]".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "exactly one of `path`, `inline` must be set for group `source`, found 2",
+    );
+}
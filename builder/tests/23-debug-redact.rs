@@ -0,0 +1,35 @@
+// With #[builder(debug)] the builder implements `Debug`, so an incomplete
+// builder can be logged when `build()` fails. Fields that were never set show
+// as `<unset>`, and the values of #[builder(redact)] fields are replaced by
+// `***` so secrets do not end up in logs.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(debug)]
+pub struct Login {
+    user: String,
+    #[builder(redact)]
+    password: String,
+    #[builder(redact)]
+    token: Option<String>,
+    #[builder(each = "scope")]
+    scopes: Vec<String>,
+}
+
+fn main() {
+    let mut builder = Login::builder();
+    assert_eq!(
+        format!("{:?}", builder),
+        "LoginBuilder { user: <unset>, password: <unset>, token: <unset>, scopes: [] }",
+    );
+
+    builder
+        .user("admin".to_owned())
+        .password("hunter2".to_owned())
+        .scope("read".to_owned());
+    assert_eq!(
+        format!("{:?}", builder),
+        r#"LoginBuilder { user: "admin", password: ***, token: <unset>, scopes: ["read"] }"#,
+    );
+}
@@ -0,0 +1,13 @@
+// #[builder(redact)] only changes the generated `Debug` impl, so it is an
+// error on a struct that does not ask for one with #[builder(debug)].
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Login {
+    user: String,
+    #[builder(redact)]
+    password: String,
+}
+
+fn main() {}
@@ -0,0 +1,57 @@
+// A builder can be reused to produce many values. `reset()` returns it to the
+// state `builder()` created it in, `unset_<field>()` clears a single field, and
+// `is_complete()` tells whether `build()` would succeed without building
+// anything.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(group(name = "target", exactly_one = [host, socket]))]
+pub struct Connection {
+    user: String,
+    host: Option<String>,
+    socket: Option<String>,
+    #[builder(each = "flag")]
+    flags: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Window {
+    width: u32,
+    height: Option<u32>,
+}
+
+const INCOMPLETE: bool = Window::builder()
+    .width(640)
+    .unset_width()
+    .is_complete();
+
+fn main() {
+    let mut builder = Connection::builder();
+    assert!(!builder.is_complete());
+
+    builder.user("root".to_owned()).host("localhost".to_owned());
+    assert!(builder.is_complete());
+
+    builder.socket("/tmp/db.sock".to_owned());
+    assert!(!builder.is_complete());
+
+    builder.unset_host().flag("verbose".to_owned());
+    assert!(builder.is_complete());
+    let connection = builder.build().unwrap();
+    assert_eq!(connection.socket.as_deref(), Some("/tmp/db.sock"));
+    assert_eq!(connection.flags, ["verbose"]);
+
+    builder.unset_flags();
+    assert!(builder.build().unwrap().flags.is_empty());
+
+    builder.reset();
+    assert!(!builder.is_complete());
+    assert!(builder.build().is_err());
+
+    assert!(!INCOMPLETE);
+    let window = Window::builder().width(800).height(600).reset().width(1024).build();
+    assert_eq!(window.width, 1024);
+    assert_eq!(window.height, None);
+}
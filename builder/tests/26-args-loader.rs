@@ -0,0 +1,50 @@
+// A struct-level #[builder(args)] attribute generates `from_args()` and
+// `apply_args(&mut self, args)` on the builder, which read `--field value` or
+// `--field=value` pairs from the command line and parse each value with
+// `FromStr`. Underscores in field names become dashes in the flag, and fields
+// with `each` take one value per occurrence of their flag.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(args)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    retry_count: Option<u8>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .apply_args(["--executable", "cargo", "--args=build", "--args", "--release"])
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.retry_count, None);
+
+    let command = Command::builder()
+        .arg("test".to_owned())
+        .apply_args(["--retry-count=3", "--executable=cargo"])
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(command.args, vec!["test"]);
+    assert_eq!(command.retry_count, Some(3));
+
+    let errors = [
+        (vec!["cargo"], "unexpected argument `cargo`"),
+        (vec!["--executable"], "missing value for `--executable`"),
+        (vec!["--verbose=true"], "unknown argument `--verbose`"),
+        (
+            vec!["--retry-count", "many"],
+            "invalid value for `--retry-count`: invalid digit found in string",
+        ),
+    ];
+    for (args, message) in errors {
+        let err = Command::builder().apply_args(args).err().unwrap();
+        assert_eq!(err.to_string(), message);
+    }
+}
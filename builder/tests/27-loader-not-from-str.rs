@@ -0,0 +1,15 @@
+// Every field that `from_env` or `from_args` loads is parsed with `FromStr`.
+// A field whose type cannot be parsed should be reported at that field, with
+// a hint for the most common case: a `Vec` without `builder(each = "...")`,
+// which would have to be parsed as a whole.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(env_prefix = "APP_", args)]
+pub struct Config {
+    host: String,
+    hosts: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,81 @@
+// Arguments passed by reference are stored as references in the builder, so
+// the builder borrows them for as long as it lives. Every elided lifetime in
+// the arguments becomes the lifetime parameter of the builder, the same one
+// that borrows `self` for methods.
+
+use derive_builder::fn_builder;
+
+#[fn_builder]
+fn greet(
+    name: &str,
+    titles: &[&str],
+    #[builder(each = "nickname")] nicknames: Vec<&str>,
+    punctuation: Option<&'static str>,
+) -> String {
+    format!(
+        "{} {name} ({}){}",
+        titles.join(" "),
+        nicknames.join(", "),
+        punctuation.unwrap_or("."),
+    )
+}
+
+#[fn_builder]
+fn longest(first: &str, second: &str) -> usize {
+    first.len().max(second.len())
+}
+
+pub struct Index {
+    words: Vec<String>,
+}
+
+#[fn_builder]
+impl Index {
+    #[fn_builder]
+    pub fn new(words: &[&str]) -> Self {
+        Index {
+            words: words.iter().map(|word| (*word).to_owned()).collect(),
+        }
+    }
+
+    #[fn_builder]
+    pub fn find(&self, prefix: &str, skip: Option<usize>) -> Option<&String> {
+        self.words
+            .iter()
+            .filter(|word| word.starts_with(prefix))
+            .nth(skip.unwrap_or(0))
+    }
+
+    #[fn_builder]
+    pub fn insert(&mut self, word: &str) {
+        self.words.push(word.to_owned());
+    }
+}
+
+fn main() {
+    let name = String::from("Ada");
+    let greeting = greet_builder()
+        .name(&name)
+        .titles(&["Countess", "of", "Lovelace"])
+        .nickname("Enchantress of Numbers")
+        .call()
+        .unwrap();
+    assert_eq!(
+        greeting,
+        "Countess of Lovelace Ada (Enchantress of Numbers).",
+    );
+
+    assert_eq!(longest_builder().first("a").second("abc").call().unwrap(), 3);
+
+    let mut index = Index::new_builder().words(&["apple", "apricot"]).call().unwrap();
+    let word = String::from("avocado");
+    index.insert_builder().word(&word).call().unwrap();
+    let found = index
+        .find_builder()
+        .prefix("a")
+        .skip(2)
+        .call()
+        .unwrap()
+        .cloned();
+    assert_eq!(found.as_deref(), Some("avocado"));
+}
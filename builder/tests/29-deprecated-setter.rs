@@ -0,0 +1,17 @@
+// The setter of a deprecated field carries the field's #[deprecated], so
+// calling it is reported by the `deprecated` lint, with the field's note.
+
+#![deny(deprecated)]
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Config {
+    name: String,
+    #[deprecated = "use `name` instead"]
+    label: Option<String>,
+}
+
+fn main() {
+    Config::builder().label("legacy".to_owned());
+}
@@ -0,0 +1,25 @@
+// Every builder has `build`, `is_complete` and `reset` methods, and an
+// `unset_<field>` method for each field next to its setter. A field whose
+// setter or unset method would have the name of another method is reported at
+// that field, instead of as a duplicate definition in the generated code.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Reset {
+    reset: bool,
+}
+
+#[derive(Builder)]
+pub struct Complete {
+    #[builder(each = "is_complete")]
+    checks: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Unset {
+    x: u32,
+    unset_x: bool,
+}
+
+fn main() {}
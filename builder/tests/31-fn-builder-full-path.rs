@@ -0,0 +1,43 @@
+// The attribute can also be written with its full path, on the impl block as
+// well as on the methods inside it. Functions with raw identifiers for names
+// get builders named after the identifier without its `r#`.
+
+pub struct Client {
+    sent: Vec<String>,
+}
+
+#[derive_builder::fn_builder]
+impl Client {
+    #[derive_builder::fn_builder]
+    pub fn send(&mut self, line: String, urgent: Option<bool>) {
+        let prefix = if urgent == Some(true) { "!" } else { "" };
+        self.sent.push(format!("{prefix}{line}"));
+    }
+}
+
+#[derive_builder::fn_builder]
+fn r#type(name: String, nullable: Option<bool>) -> String {
+    match nullable {
+        Some(true) => format!("{name}?"),
+        _ => name,
+    }
+}
+
+fn main() {
+    let ty = type_builder()
+        .name("u8".to_owned())
+        .nullable(true)
+        .call()
+        .unwrap();
+    assert_eq!(ty, "u8?");
+    let _: TypeBuilder = type_builder();
+
+    let mut client = Client { sent: Vec::new() };
+    client
+        .send_builder()
+        .line("ping".to_owned())
+        .urgent(true)
+        .call()
+        .unwrap();
+    assert_eq!(client.sent, ["!ping"]);
+}
@@ -10,4 +10,26 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-builder-trait.rs");
+    t.pass("tests/11-env-loader.rs");
+    t.pass("tests/12-skip-computed.rs");
+    t.compile_fail("tests/13-default-without-skip.rs");
+    t.pass("tests/14-required-option.rs");
+    t.compile_fail("tests/15-required-not-option.rs");
+    t.pass("tests/16-const-builder.rs");
+    t.compile_fail("tests/17-const-builder-non-copy.rs");
+    t.pass("tests/18-field-groups.rs");
+    t.compile_fail("tests/19-group-unknown-field.rs");
+    t.pass("tests/20-fn-builder.rs");
+    t.compile_fail("tests/21-fn-builder-method.rs");
+    t.pass("tests/22-cfg-deprecated.rs");
+    t.pass("tests/23-debug-redact.rs");
+    t.compile_fail("tests/24-redact-without-debug.rs");
+    t.pass("tests/25-reset-unset.rs");
+    t.pass("tests/26-args-loader.rs");
+    t.compile_fail("tests/27-loader-not-from-str.rs");
+    t.pass("tests/28-fn-builder-borrowed-args.rs");
+    t.compile_fail("tests/29-deprecated-setter.rs");
+    t.compile_fail("tests/30-method-name-collision.rs");
+    t.pass("tests/31-fn-builder-full-path.rs");
 }
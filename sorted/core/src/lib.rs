@@ -0,0 +1,11 @@
+// The sort orders, sort keys and markers shared by the `sorted` macros and `sorted-fix`.
+
+mod marker;
+mod order;
+mod sortable;
+
+pub use marker::{Markers, Pin};
+pub use order::Order;
+pub use sortable::{
+    check_kinds, is_binding, Kind, SequencePattern, SimplifiedPath, Sortable, Value, ValuePattern,
+};
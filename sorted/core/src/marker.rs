@@ -0,0 +1,67 @@
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+
+/// Where a variant or match arm has to go, chosen with `#[sorted(first)]`, `#[sorted(last)]` or
+/// `#[sorted(skip)]` on it
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Pin {
+    /// In order with the other elements
+    #[default]
+    Sorted,
+    /// `#[sorted(first)]`, before every element not pinned first
+    First,
+    /// `#[sorted(last)]`, after every element not pinned last, other than wildcards
+    Last,
+    /// `#[sorted(skip)]`, anywhere
+    Skip,
+}
+
+/// The `#[sorted(...)]` markers on a variant or match arm
+#[derive(Clone, Copy, Default)]
+pub struct Markers {
+    pub pin: Pin,
+    /// The attribute giving the pin, if any
+    pub pin_attr: Option<Span>,
+    /// The attribute with `section`, which starts a section sorted independently of the elements
+    /// before it
+    pub section: Option<Span>,
+}
+
+impl Markers {
+    /// Parse the markers given by the `#[sorted(...)]` attributes of a variant or match arm
+    pub fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut markers = Markers::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("sorted")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("section") {
+                    markers.section = Some(attr.span());
+                    return Ok(());
+                }
+                let pin = if meta.path.is_ident("first") {
+                    Pin::First
+                } else if meta.path.is_ident("last") {
+                    Pin::Last
+                } else if meta.path.is_ident("skip") {
+                    Pin::Skip
+                } else {
+                    return Err(meta.error("expected `first`, `last`, `skip` or `section`"));
+                };
+                if markers.pin != Pin::Sorted {
+                    return Err(meta.error("only one of `first`, `last` or `skip` can be given"));
+                }
+                markers.pin = pin;
+                markers.pin_attr = Some(attr.span());
+                Ok(())
+            })?;
+        }
+        Ok(markers)
+    }
+
+    /// Remove the `#[sorted(...)]` attributes of a variant or match arm, and parse the markers
+    /// they give
+    pub fn take(attrs: &mut Vec<syn::Attribute>) -> syn::Result<Self> {
+        let markers = Self::parse(attrs)?;
+        attrs.retain(|attr| !attr.path().is_ident("sorted"));
+        Ok(markers)
+    }
+}
@@ -0,0 +1,94 @@
+use std::cmp::Ordering;
+
+/// How names are compared, chosen with `#[sorted(order = "...")]`
+#[derive(Clone, Copy, Default)]
+pub enum Order {
+    /// Byte-wise, so uppercase sorts before lowercase
+    #[default]
+    Lexicographic,
+    /// `order = "case_insensitive"`
+    CaseInsensitive,
+    /// `order = "natural"`, runs of digits compare by their numeric value, so `Irq2` sorts
+    /// before `Irq10`
+    Natural,
+    /// `order = "descending"`, byte-wise but reversed
+    Descending,
+}
+
+impl Order {
+    /// Parse the value of `order = "..."`
+    pub fn parse(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Self> {
+        let lit = meta.value()?.parse::<syn::LitStr>()?;
+        match lit.value().as_str() {
+            "case_insensitive" => Ok(Order::CaseInsensitive),
+            "natural" => Ok(Order::Natural),
+            "descending" => Ok(Order::Descending),
+            _ => Err(syn::Error::new(
+                lit.span(),
+                "expected `\"case_insensitive\"`, `\"natural\"` or `\"descending\"`",
+            )),
+        }
+    }
+
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        match self {
+            Order::Lexicographic => a.cmp(b),
+            // fall back to byte-wise for names that only differ in case, so the order is total
+            Order::CaseInsensitive => a
+                .to_lowercase()
+                .cmp(&b.to_lowercase())
+                .then_with(|| a.cmp(b)),
+            Order::Natural => compare_natural(a, b).then_with(|| a.cmp(b)),
+            Order::Descending => b.cmp(a),
+        }
+    }
+}
+
+/// Compare strings chunk by chunk, where each chunk is either a run of digits, compared by
+/// numeric value, or a run of anything else, compared byte-wise
+fn compare_natural(a: &str, b: &str) -> Ordering {
+    let mut a_chunks = Chunks(a);
+    let mut b_chunks = Chunks(b);
+    loop {
+        let ordering = match (a_chunks.next(), b_chunks.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) if is_digits(a) && is_digits(b) => {
+                // compare without parsing, so arbitrarily long runs of digits work too
+                let a = a.trim_start_matches('0');
+                let b = b.trim_start_matches('0');
+                a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+            }
+            (Some(a), Some(b)) => a.cmp(b),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn is_digits(chunk: &str) -> bool {
+    chunk.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Splits a string into alternating runs of ASCII digits and other characters
+struct Chunks<'a>(&'a str);
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let digits = is_digits(self.0);
+        let end = self
+            .0
+            .find(|c: char| c.is_ascii_digit() != digits)
+            .unwrap_or(self.0.len());
+        if end == 0 {
+            return None;
+        }
+        let (chunk, rest) = self.0.split_at(end);
+        self.0 = rest;
+        Some(chunk)
+    }
+}
@@ -0,0 +1,430 @@
+use crate::order::Order;
+use proc_macro2::Span;
+use quote::ToTokens;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+
+#[derive(Clone)]
+pub struct SimplifiedPath<'ast> {
+    pub source: &'ast syn::Path,
+    pub stringified: String,
+}
+
+impl<'ast> TryFrom<&'ast syn::Path> for SimplifiedPath<'ast> {
+    type Error = syn::Error;
+    fn try_from(path: &'ast syn::Path) -> Result<Self, syn::Error> {
+        if path.leading_colon.is_some() {
+            return Err(syn::Error::new(
+                path.leading_colon.span(),
+                "unsupported by #[sorted]",
+            ));
+        }
+        let mut stringified = String::new();
+        for segment in &path.segments {
+            if !matches!(segment.arguments, syn::PathArguments::None) {
+                return Err(syn::Error::new(
+                    segment.arguments.span(),
+                    "unsupported by #[sorted]",
+                ));
+            }
+
+            stringified = if stringified.is_empty() {
+                segment.ident.to_string()
+            } else {
+                format!("{stringified}::{}", segment.ident)
+            };
+        }
+
+        Ok(Self {
+            source: path,
+            stringified,
+        })
+    }
+}
+
+/// What kind of value a pattern matches, only patterns of the same kind can be compared
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    Path,
+    Integer,
+    Char,
+    Byte,
+    String,
+    Tuple,
+    Slice,
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Path => "path".fmt(f),
+            Kind::Integer => "integer".fmt(f),
+            Kind::Char => "char".fmt(f),
+            Kind::Byte => "byte".fmt(f),
+            Kind::String => "string".fmt(f),
+            Kind::Tuple => "tuple".fmt(f),
+            Kind::Slice => "slice".fmt(f),
+        }
+    }
+}
+
+/// What a literal or range pattern matches
+#[derive(Clone)]
+pub enum Value {
+    /// Integers, chars and bytes, as the inclusive range of values matched, with chars and bytes
+    /// converted to integers
+    Number {
+        kind: Kind,
+        low: i128,
+        high: i128,
+    },
+    String(String),
+}
+
+/// A literal or range pattern
+#[derive(Clone)]
+pub struct ValuePattern<'ast> {
+    pub source: &'ast syn::Pat,
+    pub value: Value,
+    /// The pattern as written, for messages
+    pub text: String,
+}
+
+impl<'ast> TryFrom<&'ast syn::Pat> for ValuePattern<'ast> {
+    type Error = syn::Error;
+    fn try_from(pat: &'ast syn::Pat) -> Result<Self, syn::Error> {
+        let value = match pat {
+            syn::Pat::Lit(pat_lit) => literal_value(&pat_lit.lit)?,
+            syn::Pat::Range(pat_range) => {
+                let start = pat_range.start.as_deref().map(range_bound).transpose()?;
+                let end = pat_range.end.as_deref().map(range_bound).transpose()?;
+                let kind = match (start, end) {
+                    (Some((kind, _)), _) | (None, Some((kind, _))) => kind,
+                    (None, None) => {
+                        return Err(syn::Error::new(pat.span(), "unsupported by #[sorted]"));
+                    }
+                };
+                let high = match (end, &pat_range.limits) {
+                    (Some((_, end)), syn::RangeLimits::Closed(_)) => end,
+                    (Some((_, end)), syn::RangeLimits::HalfOpen(_)) => end - 1,
+                    (None, _) => i128::MAX,
+                };
+                Value::Number {
+                    kind,
+                    low: start.map_or(i128::MIN, |(_, start)| start),
+                    high,
+                }
+            }
+            _ => return Err(syn::Error::new(pat.span(), "unsupported by #[sorted]")),
+        };
+
+        Ok(Self {
+            source: pat,
+            value,
+            text: pattern_text(pat),
+        })
+    }
+}
+
+impl ValuePattern<'_> {
+    pub fn kind(&self) -> Kind {
+        match &self.value {
+            Value::Number { kind, .. } => *kind,
+            Value::String(_) => Kind::String,
+        }
+    }
+
+    /// Compare numbers by value, ranges by where they start, and strings using `order`
+    ///
+    /// Only `order = "descending"` applies to numbers, reversing them.
+    fn compare(&self, other: &Self, order: Order) -> Ordering {
+        match (&self.value, &other.value) {
+            (
+                Value::Number { low, high, .. },
+                Value::Number {
+                    low: other_low,
+                    high: other_high,
+                    ..
+                },
+            ) => {
+                let ordering = (low, high).cmp(&(other_low, other_high));
+                match order {
+                    Order::Descending => ordering.reverse(),
+                    _ => ordering,
+                }
+            }
+            (Value::String(a), Value::String(b)) => order.compare(a, b),
+            _ => self.kind().cmp(&other.kind()),
+        }
+    }
+}
+
+fn literal_value(lit: &syn::Lit) -> syn::Result<Value> {
+    let (kind, value) = match lit {
+        syn::Lit::Int(lit_int) => (Kind::Integer, lit_int.base10_parse()?),
+        syn::Lit::Char(lit_char) => (Kind::Char, u32::from(lit_char.value()).into()),
+        syn::Lit::Byte(lit_byte) => (Kind::Byte, lit_byte.value().into()),
+        syn::Lit::Str(lit_str) => return Ok(Value::String(lit_str.value())),
+        _ => return Err(syn::Error::new(lit.span(), "unsupported by #[sorted]")),
+    };
+    Ok(Value::Number {
+        kind,
+        low: value,
+        high: value,
+    })
+}
+
+/// The kind and value of a range pattern bound
+fn range_bound(expr: &syn::Expr) -> syn::Result<(Kind, i128)> {
+    if let syn::Expr::Lit(expr_lit) = expr {
+        if let Value::Number { kind, low, .. } = literal_value(&expr_lit.lit)? {
+            return Ok((kind, low));
+        }
+    }
+    Err(syn::Error::new(expr.span(), "unsupported by #[sorted]"))
+}
+
+/// Print a literal or range pattern without the spaces a token stream would put around `..`
+fn pattern_text(pat: &syn::Pat) -> String {
+    match pat {
+        syn::Pat::Range(pat_range) => {
+            let bound = |bound: &Option<Box<syn::Expr>>| {
+                bound
+                    .as_ref()
+                    .map(|expr| expr.to_token_stream().to_string())
+                    .unwrap_or_default()
+            };
+            let limits = match pat_range.limits {
+                syn::RangeLimits::HalfOpen(_) => "..",
+                syn::RangeLimits::Closed(_) => "..=",
+            };
+            format!(
+                "{}{limits}{}",
+                bound(&pat_range.start),
+                bound(&pat_range.end)
+            )
+        }
+        _ => pat.to_token_stream().to_string(),
+    }
+}
+
+/// A tuple or slice pattern, compared element by element
+#[derive(Clone)]
+pub struct SequencePattern<'ast> {
+    pub source: &'ast syn::Pat,
+    pub elems: Vec<Sortable<'ast>>,
+}
+
+impl SequencePattern<'_> {
+    /// Compare the first elements that differ, or if one is a prefix of the other, put the
+    /// shorter one first
+    fn compare(&self, other: &Self, order: Order) -> Ordering {
+        self.elems
+            .iter()
+            .zip(&other.elems)
+            .map(|(a, b)| a.compare(b, order))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| self.elems.len().cmp(&other.elems.len()))
+    }
+
+    fn fmt(&self, f: &mut Formatter<'_>, open: char, close: char) -> std::fmt::Result {
+        write!(f, "{open}")?;
+        for (i, elem) in self.elems.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            match elem {
+                Sortable::Wildcard(_) => write!(f, "_")?,
+                Sortable::Rest(_) => write!(f, "..")?,
+                _ => write!(f, "{elem}")?,
+            }
+        }
+        write!(f, "{close}")
+    }
+}
+
+#[derive(Clone)]
+pub enum Sortable<'ast> {
+    Ident(&'ast syn::Ident),
+    Path(SimplifiedPath<'ast>),
+    Value(ValuePattern<'ast>),
+    Tuple(SequencePattern<'ast>),
+    Slice(SequencePattern<'ast>),
+    Wildcard(&'ast syn::Token![_]),
+    /// A plain binding like `other`, matching anything like a wildcard
+    Binding(&'ast syn::Ident),
+    /// `..` in a tuple or slice pattern, sorting last like a wildcard
+    Rest(&'ast syn::PatRest),
+}
+
+impl<'ast> Sortable<'ast> {
+    pub fn span(&self) -> Span {
+        match self {
+            Sortable::Ident(ident) => ident.span(),
+            Sortable::Path(path) => path.source.span(),
+            Sortable::Value(value) => value.source.span(),
+            Sortable::Tuple(tuple) => tuple.source.span(),
+            Sortable::Slice(slice) => slice.source.span(),
+            Sortable::Wildcard(wildcard) => wildcard.span,
+            Sortable::Binding(ident) => ident.span(),
+            Sortable::Rest(rest) => rest.span(),
+        }
+    }
+
+    /// The kind of this element, `None` for wildcards and bindings which go with any kind
+    pub fn kind(&self) -> Option<Kind> {
+        match self {
+            Sortable::Ident(_) | Sortable::Path(_) => Some(Kind::Path),
+            Sortable::Value(value) => Some(value.kind()),
+            Sortable::Tuple(_) => Some(Kind::Tuple),
+            Sortable::Slice(_) => Some(Kind::Slice),
+            Sortable::Wildcard(_) | Sortable::Binding(_) | Sortable::Rest(_) => None,
+        }
+    }
+
+    /// The elements of a tuple or slice pattern
+    pub fn elems(&self) -> Option<&[Self]> {
+        match self {
+            Sortable::Tuple(sequence) | Sortable::Slice(sequence) => Some(&sequence.elems),
+            _ => None,
+        }
+    }
+
+    /// Find what a match arm with the given pattern is sorted by
+    ///
+    /// Literals and ranges are sorted by value, and tuples and slices element by element.
+    /// Plain bindings match anything, so they sort last like `_`. Bindings with a subpattern,
+    /// references and parentheses are sorted by the pattern they contain, and or-patterns by
+    /// their first alternative. The alternatives of every or-pattern are pushed to
+    /// `alternatives`, as they have to be sorted among themselves too.
+    pub fn from_pat(
+        pat: &'ast syn::Pat,
+        alternatives: &mut Vec<Vec<Sortable<'ast>>>,
+    ) -> syn::Result<Self> {
+        let sortable = match pat {
+            syn::Pat::Ident(pat_ident) => match &pat_ident.subpat {
+                Some((_, subpat)) => Self::from_pat(subpat, alternatives)?,
+                None if is_binding(&pat_ident.ident) => Sortable::Binding(&pat_ident.ident),
+                None => Sortable::Ident(&pat_ident.ident),
+            },
+            syn::Pat::Path(pat_path) => Sortable::Path(SimplifiedPath::try_from(&pat_path.path)?),
+            syn::Pat::Struct(pat_struct) => {
+                Sortable::Path(SimplifiedPath::try_from(&pat_struct.path)?)
+            }
+            syn::Pat::TupleStruct(tuple_struct) => {
+                Sortable::Path(SimplifiedPath::try_from(&tuple_struct.path)?)
+            }
+            syn::Pat::Lit(_) | syn::Pat::Range(_) => Sortable::Value(ValuePattern::try_from(pat)?),
+            syn::Pat::Tuple(pat_tuple) => Sortable::Tuple(SequencePattern {
+                source: pat,
+                elems: Self::from_pats(&pat_tuple.elems, alternatives)?,
+            }),
+            syn::Pat::Slice(pat_slice) => Sortable::Slice(SequencePattern {
+                source: pat,
+                elems: Self::from_pats(&pat_slice.elems, alternatives)?,
+            }),
+            syn::Pat::Wild(pat_wild) => Sortable::Wildcard(&pat_wild.underscore_token),
+            syn::Pat::Rest(pat_rest) => Sortable::Rest(pat_rest),
+            syn::Pat::Reference(pat_reference) => Self::from_pat(&pat_reference.pat, alternatives)?,
+            syn::Pat::Paren(pat_paren) => Self::from_pat(&pat_paren.pat, alternatives)?,
+            syn::Pat::Or(pat_or) => {
+                let cases = Self::from_pats(&pat_or.cases, alternatives)?;
+                let first = cases[0].clone();
+                alternatives.push(cases);
+                first
+            }
+            _ => return Err(syn::Error::new(pat.span(), "unsupported by #[sorted]")),
+        };
+        Ok(sortable)
+    }
+
+    fn from_pats<P>(
+        pats: &'ast Punctuated<syn::Pat, P>,
+        alternatives: &mut Vec<Vec<Sortable<'ast>>>,
+    ) -> syn::Result<Vec<Self>> {
+        pats.iter()
+            .map(|pat| Self::from_pat(pat, alternatives))
+            .collect()
+    }
+
+    pub fn is_wildcard(&self) -> bool {
+        matches!(
+            self,
+            Sortable::Wildcard(_) | Sortable::Binding(_) | Sortable::Rest(_)
+        )
+    }
+
+    /// Compare by name using `order`, with wildcards and bindings after everything else, whatever
+    /// the order
+    pub fn compare(&self, other: &Self, order: Order) -> Ordering {
+        match (self, other) {
+            (a, b) if a.is_wildcard() || b.is_wildcard() => a.is_wildcard().cmp(&b.is_wildcard()),
+            (Self::Value(a), Self::Value(b)) => a.compare(b, order),
+            (Self::Tuple(a), Self::Tuple(b)) | (Self::Slice(a), Self::Slice(b)) => {
+                a.compare(b, order)
+            }
+            (a, b) => order.compare(&a.to_string(), &b.to_string()),
+        }
+    }
+}
+
+impl Display for Sortable<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ident(ident) => ident.fmt(f),
+            Sortable::Path(path) => path.stringified.fmt(f),
+            Sortable::Value(value) => value.text.fmt(f),
+            Sortable::Tuple(tuple) => tuple.fmt(f, '(', ')'),
+            Sortable::Slice(slice) => slice.fmt(f, '[', ']'),
+            Sortable::Wildcard(_) => "wildcard".fmt(f),
+            Sortable::Binding(ident) => ident.fmt(f),
+            Sortable::Rest(_) => "rest pattern".fmt(f),
+        }
+    }
+}
+
+/// Whether a pattern that is a single identifier is a binding, rather than a unit variant or
+/// constant brought into scope with `use`
+///
+/// Patterns cannot tell them apart, so this goes by the naming conventions rustc lints for:
+/// bindings are snake case, variants and constants start with an uppercase letter.
+pub fn is_binding(ident: &syn::Ident) -> bool {
+    !ident
+        .unraw()
+        .to_string()
+        .starts_with(|c: char| c.is_uppercase())
+}
+
+/// Check that the elements are all paths, all literals and ranges of the same kind, or all
+/// tuples or slices whose elements are checked the same way position by position, as otherwise
+/// they cannot be compared
+pub fn check_kinds(elements: &[&Sortable]) -> Option<syn::Error> {
+    let mut kinds = elements
+        .iter()
+        .filter_map(|sortable| Some((sortable, sortable.kind()?)));
+    if let Some((_, kind)) = kinds.next() {
+        if let Some((other, other_kind)) = kinds.find(|&(_, other_kind)| other_kind != kind) {
+            return Some(syn::Error::new(
+                other.span(),
+                format!("cannot sort {other_kind} pattern {other} among {kind} patterns"),
+            ));
+        }
+    }
+
+    let sequences = elements
+        .iter()
+        .filter_map(|sortable| sortable.elems())
+        .collect::<Vec<_>>();
+    let len = sequences.iter().map(|elems| elems.len()).max().unwrap_or(0);
+    (0..len).find_map(|i| {
+        check_kinds(
+            &sequences
+                .iter()
+                .filter_map(|elems| elems.get(i))
+                .collect::<Vec<_>>(),
+        )
+    })
+}
@@ -0,0 +1,496 @@
+use sorted_core::{check_kinds, Markers, Order, Pin, SequencePattern, Sortable, Value};
+use std::cmp::Ordering;
+use std::ops::Range;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+/// Reorder the variants of `#[sorted]` enums, and the arms of `#[sorted]` matches inside
+/// `#[sorted::check]` functions, impl blocks, traits and modules, leaving the rest of the source
+/// text untouched
+///
+/// Attributes and doc comments move along with their variant or arm. Ordinary comments between
+/// them stay where they are. Variants and arms pinned with `#[sorted(first)]` or
+/// `#[sorted(last)]` are moved to the start or end, keeping their order among themselves, and
+/// those marked `#[sorted(skip)]` are not moved. Each section started by `#[sorted(section)]` is
+/// sorted on its own. The alternatives of or-patterns are sorted too.
+///
+/// Moving an arm must not change which arm a value goes to, so arms whose patterns may overlap,
+/// like any arm and a later wildcard or catch-all binding, or a guarded arm and one it guards
+/// against, are never moved past each other. Their order is left to the author.
+pub fn fix_source(source: &str) -> syn::Result<String> {
+    let mut source = source.to_owned();
+    // lists nested in a moved element are fixed by a later pass, on the reparsed source
+    loop {
+        let file = syn::parse_file(&source)?;
+        let mut collector = EditCollector {
+            source: &source,
+            check_order: None,
+            edits: Vec::new(),
+        };
+        collector.visit_file(&file);
+        if collector.edits.is_empty() {
+            return Ok(source);
+        }
+        source = apply_edits(&source, collector.edits);
+    }
+}
+
+/// A replacement of `range` in the source by `text`
+struct Edit {
+    range: Range<usize>,
+    text: String,
+}
+
+/// A variant or arm, with the text it occupies in the source and the text it is moved as
+struct Element<'ast> {
+    range: Range<usize>,
+    text: String,
+    key: Sortable<'ast>,
+    pin: Pin,
+    /// The `#[sorted(section)]` attribute starting a new section with this element, which stays
+    /// at the start of the section when the element moves
+    section: Option<Range<usize>>,
+}
+
+impl Element<'_> {
+    /// Compare elements pinned first, then the sorted ones by key, then wildcards and elements
+    /// pinned last, which are left in the order they were written
+    fn compare(&self, other: &Self, order: Order) -> Ordering {
+        match self.group().cmp(&other.group()) {
+            Ordering::Equal if self.group() == 1 => self.key.compare(&other.key, order),
+            ordering => ordering,
+        }
+    }
+
+    fn group(&self) -> u8 {
+        match (self.pin, &self.key) {
+            (Pin::First, _) => 0,
+            (Pin::Last, _) => 2,
+            (_, key) if key.is_wildcard() => 2,
+            _ => 1,
+        }
+    }
+}
+
+struct EditCollector<'a> {
+    source: &'a str,
+    /// The order of the enclosing `#[sorted::check]` item, if any
+    check_order: Option<Order>,
+    edits: Vec<Edit>,
+}
+
+impl<'ast> Visit<'ast> for EditCollector<'_> {
+    fn visit_item_enum(&mut self, node: &'ast syn::ItemEnum) {
+        if let Some(order) = sorted_attr_order(&node.attrs, is_sorted_path, Order::default()) {
+            let elements = node
+                .variants
+                .iter()
+                .map(|variant| {
+                    let range = variant.span().byte_range();
+                    let (pin, section) = markers(&variant.attrs)?;
+                    Some(Element {
+                        text: self.source[range.clone()].to_owned(),
+                        range,
+                        key: Sortable::Ident(&variant.ident),
+                        pin,
+                        section,
+                    })
+                })
+                .collect::<Option<_>>();
+            if let Some(elements) = elements {
+                self.reorder(elements, order, |_, _| false);
+            }
+        }
+        visit::visit_item_enum(self, node);
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.within_check(&node.attrs, |this| visit::visit_item_fn(this, node));
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        self.within_check(&node.attrs, |this| visit::visit_item_impl(this, node));
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast syn::ItemTrait) {
+        self.within_check(&node.attrs, |this| visit::visit_item_trait(this, node));
+    }
+
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        self.within_check(&node.attrs, |this| visit::visit_item_mod(this, node));
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.within_check(&node.attrs, |this| visit::visit_impl_item_fn(this, node));
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+        self.within_check(&node.attrs, |this| visit::visit_trait_item_fn(this, node));
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast syn::ExprMatch) {
+        if let Some(check_order) = self.check_order {
+            let order = sorted_attr_order(&node.attrs, |path| path.is_ident("sorted"), check_order);
+            let elements = node
+                .arms
+                .iter()
+                .map(|arm| self.arm_element(arm))
+                .collect::<Option<Vec<_>>>();
+            if let (Some(order), Some(elements)) = (order, elements) {
+                for arm in &node.arms {
+                    self.reorder_alternatives(&arm.pat, order);
+                }
+
+                // `#[sorted]` rejects matches mixing patterns that cannot be compared
+                let keys = elements
+                    .iter()
+                    .map(|element| &element.key)
+                    .collect::<Vec<_>>();
+                let cases = node
+                    .arms
+                    .iter()
+                    .map(|arm| cases(&arm.pat))
+                    .collect::<Vec<_>>();
+                let overlaps = |i: usize, j: usize| cases_overlap(&cases[i], &cases[j]);
+                // arms marked `#[sorted(skip)]` keep their slot, so nothing may move past one
+                // it overlaps
+                let skip_overlaps = (0..elements.len()).any(|i| {
+                    elements[i].pin == Pin::Skip
+                        && (0..elements.len()).any(|j| j != i && overlaps(i, j))
+                });
+                if check_kinds(&keys).is_none() && !skip_overlaps {
+                    self.reorder(elements, order, overlaps);
+                }
+            }
+        }
+        visit::visit_expr_match(self, node);
+    }
+}
+
+impl EditCollector<'_> {
+    /// Visit an item, with its `#[sorted::check]` order if it has one
+    fn within_check(&mut self, attrs: &[syn::Attribute], visit: impl FnOnce(&mut Self)) {
+        let outer = self.check_order;
+        if let Some(order) = sorted_attr_order(attrs, is_check_path, Order::default()) {
+            self.check_order = Some(order);
+        }
+        visit(self);
+        self.check_order = outer;
+    }
+
+    /// Produce the element for a match arm, or `None` if its pattern cannot be sorted
+    fn arm_element<'ast>(&self, arm: &'ast syn::Arm) -> Option<Element<'ast>> {
+        let key = Sortable::from_pat(&arm.pat, &mut Vec::new()).ok()?;
+        let (pin, section) = markers(&arm.attrs)?;
+
+        // the comma is part of the arm, so an arm moved away from the end gets one if it needs it
+        let range = arm.span().byte_range();
+        let body_end = arm.body.span().byte_range().end;
+        let mut text = self.source[range.start..body_end].to_owned();
+        if arm.comma.is_some() || !matches!(&*arm.body, syn::Expr::Block(_)) {
+            text.push(',');
+        }
+        Some(Element {
+            range,
+            text,
+            key,
+            pin,
+            section,
+        })
+    }
+
+    /// Record edits putting the alternatives of the or-patterns in `pat` in order, wherever
+    /// `#[sorted]` requires them to be
+    ///
+    /// The order of alternatives does not change what a pattern matches, so they can always move.
+    fn reorder_alternatives(&mut self, pat: &syn::Pat, order: Order) {
+        match pat {
+            syn::Pat::Or(pat_or) => {
+                let elements = pat_or
+                    .cases
+                    .iter()
+                    .map(|case| {
+                        let range = case.span().byte_range();
+                        Some(Element {
+                            text: self.source[range.clone()].to_owned(),
+                            range,
+                            key: Sortable::from_pat(case, &mut Vec::new()).ok()?,
+                            pin: Pin::Sorted,
+                            section: None,
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>();
+                if let Some(elements) = elements {
+                    let keys = elements
+                        .iter()
+                        .map(|element| &element.key)
+                        .collect::<Vec<_>>();
+                    if check_kinds(&keys).is_none() {
+                        self.reorder(elements, order, |_, _| false);
+                    }
+                }
+                for case in &pat_or.cases {
+                    self.reorder_alternatives(case, order);
+                }
+            }
+            syn::Pat::Tuple(pat_tuple) => {
+                for elem in &pat_tuple.elems {
+                    self.reorder_alternatives(elem, order);
+                }
+            }
+            syn::Pat::Slice(pat_slice) => {
+                for elem in &pat_slice.elems {
+                    self.reorder_alternatives(elem, order);
+                }
+            }
+            syn::Pat::Ident(syn::PatIdent {
+                subpat: Some((_, subpat)),
+                ..
+            }) => self.reorder_alternatives(subpat, order),
+            syn::Pat::Reference(pat_reference) => {
+                self.reorder_alternatives(&pat_reference.pat, order)
+            }
+            syn::Pat::Paren(pat_paren) => self.reorder_alternatives(&pat_paren.pat, order),
+            _ => {}
+        }
+    }
+
+    /// Record an edit putting `elements` in order, if they are not already
+    ///
+    /// Elements for which `overlaps` is true keep their order relative to each other.
+    fn reorder(
+        &mut self,
+        elements: Vec<Element<'_>>,
+        order: Order,
+        overlaps: impl Fn(usize, usize) -> bool,
+    ) {
+        // each section is sorted on its own, and within it elements marked `#[sorted(skip)]` keep
+        // their slot while the others are sorted into the rest
+        let mut sorted = (0..elements.len()).collect::<Vec<_>>();
+        let mut section_start = 0;
+        while section_start < elements.len() {
+            let section_end = (section_start + 1..elements.len())
+                .find(|&i| elements[i].section.is_some())
+                .unwrap_or(elements.len());
+            let slots = (section_start..section_end)
+                .filter(|&i| elements[i].pin != Pin::Skip)
+                .collect::<Vec<_>>();
+            // take the least element each time, among those no element before them overlaps
+            let mut remaining = slots.clone();
+            let mut movable = Vec::with_capacity(slots.len());
+            while let Some(next) = remaining
+                .iter()
+                .copied()
+                .filter(|&j| !remaining.iter().any(|&i| i < j && overlaps(i, j)))
+                .min_by(|&a, &b| elements[a].compare(&elements[b], order).then(a.cmp(&b)))
+            {
+                remaining.retain(|&i| i != next);
+                movable.push(next);
+            }
+            for (&slot, &index) in slots.iter().zip(&movable) {
+                sorted[slot] = index;
+            }
+            section_start = section_end;
+        }
+        if sorted
+            .iter()
+            .enumerate()
+            .all(|(position, &index)| position == index)
+        {
+            return;
+        }
+
+        let mut texts = elements
+            .iter()
+            .map(|element| element.text.clone())
+            .collect::<Vec<_>>();
+        for (slot, element) in elements.iter().enumerate() {
+            if let Some(attr) = &element.section {
+                if sorted[slot] != slot {
+                    // the attribute goes with whatever follows it up to the next token
+                    let rest = &self.source[attr.end..];
+                    let end = attr.end + rest.len() - rest.trim_start().len();
+                    let offset = attr.start - element.range.start;
+                    texts[slot].replace_range(offset..offset + (end - attr.start), "");
+                    texts[sorted[slot]].insert_str(0, &self.source[attr.start..end]);
+                }
+            }
+        }
+
+        let start = elements[0].range.start;
+        let mut text = String::new();
+        let mut gap_start = start;
+        for (slot, &index) in elements.iter().zip(&sorted) {
+            text.push_str(&self.source[gap_start..slot.range.start]);
+            text.push_str(&texts[index]);
+            gap_start = slot.range.end;
+        }
+        self.edits.push(Edit {
+            range: start..gap_start,
+            text,
+        });
+    }
+}
+
+fn is_sorted_path(path: &syn::Path) -> bool {
+    path.is_ident("sorted") || is_sorted_crate_path(path, "sorted")
+}
+
+fn is_check_path(path: &syn::Path) -> bool {
+    is_sorted_crate_path(path, "check")
+}
+
+/// Whether `path` is `sorted::{name}`
+fn is_sorted_crate_path(path: &syn::Path, name: &str) -> bool {
+    path.segments.len() == 2 && path.segments[0].ident == "sorted" && path.segments[1].ident == name
+}
+
+/// Find where the `#[sorted(...)]` attributes of a variant or arm pin it and the one starting a
+/// section, if any
+///
+/// Returns `None` if they are malformed, or if `section` is combined with a pin in one attribute,
+/// as the attribute could then not be left at the start of the section on its own.
+fn markers(attrs: &[syn::Attribute]) -> Option<(Pin, Option<Range<usize>>)> {
+    let markers = Markers::parse(attrs).ok()?;
+    let section = markers.section.map(|span| span.byte_range());
+    if section.is_some() && section == markers.pin_attr.map(|span| span.byte_range()) {
+        return None;
+    }
+    Some((markers.pin, section))
+}
+
+/// Find the attribute matching `is_attr` and the order it asks for, `default` if it does not
+///
+/// Returns `None` if there is no such attribute, if it asks for the order the variants are
+/// declared in with `declared` or `as_declared`, which is left to the author, or if it is
+/// malformed, in which case the macro reports it at compile time and the element is left alone.
+fn sorted_attr_order(
+    attrs: &[syn::Attribute],
+    is_attr: impl Fn(&syn::Path) -> bool,
+    default: Order,
+) -> Option<Order> {
+    let attr = attrs.iter().find(|attr| is_attr(attr.path()))?;
+    let mut order = default;
+    let mut declared = false;
+    if let syn::Meta::List(_) = &attr.meta {
+        attr.parse_nested_meta(|meta| {
+            declared |= meta.path.is_ident("declared") || meta.path.is_ident("as_declared");
+            if meta.path.is_ident("order") {
+                order = Order::parse(&meta)?;
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+            Ok(())
+        })
+        .ok()?;
+    }
+    if declared {
+        return None;
+    }
+    Some(order)
+}
+
+/// Whether a value could match the patterns of two arms, given by their [`cases`]
+fn cases_overlap(a: &Option<Vec<Sortable>>, b: &Option<Vec<Sortable>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.iter().any(|a| b.iter().any(|b| may_overlap(a, b))),
+        _ => true,
+    }
+}
+
+/// The sort keys of the alternatives of a pattern, or of the pattern itself if it has none
+///
+/// Returns `None` if an alternative cannot be sorted, or has or-patterns nested inside, whose
+/// keys only stand for their first alternative.
+fn cases(pat: &syn::Pat) -> Option<Vec<Sortable<'_>>> {
+    let pats = match pat {
+        syn::Pat::Or(pat_or) => pat_or.cases.iter().collect(),
+        syn::Pat::Paren(pat_paren) => return cases(&pat_paren.pat),
+        pat => vec![pat],
+    };
+    pats.into_iter()
+        .map(|pat| {
+            let mut alternatives = Vec::new();
+            let key = Sortable::from_pat(pat, &mut alternatives).ok()?;
+            alternatives.is_empty().then_some(key)
+        })
+        .collect()
+}
+
+/// Whether a value could match both keys, erring on the side of yes
+///
+/// Paths only tell which variant they match, so two paths naming the same one may overlap.
+fn may_overlap(a: &Sortable, b: &Sortable) -> bool {
+    match (a, b) {
+        (a, b) if a.is_wildcard() || b.is_wildcard() => true,
+        (Sortable::Value(a), Sortable::Value(b)) => match (&a.value, &b.value) {
+            (
+                Value::Number { kind, low, high },
+                Value::Number {
+                    kind: other_kind,
+                    low: other_low,
+                    high: other_high,
+                },
+            ) => kind == other_kind && low <= other_high && other_low <= high,
+            (Value::String(a), Value::String(b)) => a == b,
+            _ => true,
+        },
+        (Sortable::Tuple(a), Sortable::Tuple(b)) | (Sortable::Slice(a), Sortable::Slice(b)) => {
+            sequences_overlap(a, b)
+        }
+        (a, b) => match (last_ident(a), last_ident(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        },
+    }
+}
+
+/// Whether a value could match both tuple or slice patterns, compared position by position
+fn sequences_overlap(a: &SequencePattern, b: &SequencePattern) -> bool {
+    let has_rest = |sequence: &SequencePattern| {
+        sequence
+            .elems
+            .iter()
+            .any(|elem| matches!(elem, Sortable::Rest(_)))
+    };
+    if has_rest(a) || has_rest(b) {
+        return true;
+    }
+    a.elems.len() == b.elems.len() && a.elems.iter().zip(&b.elems).all(|(a, b)| may_overlap(a, b))
+}
+
+/// The name of the variant or constant a path key matches
+fn last_ident<'ast>(key: &Sortable<'ast>) -> Option<&'ast syn::Ident> {
+    match key {
+        Sortable::Ident(ident) => Some(ident),
+        Sortable::Path(path) => Some(&path.source.segments.last()?.ident),
+        _ => None,
+    }
+}
+
+/// Apply the edits from last to first so earlier ranges stay valid
+///
+/// Edits nested in another edit are dropped, the next pass picks them up again.
+fn apply_edits(source: &str, mut edits: Vec<Edit>) -> String {
+    edits.sort_by(|a, b| {
+        a.range
+            .start
+            .cmp(&b.range.start)
+            .then(b.range.end.cmp(&a.range.end))
+    });
+    let mut outermost: Vec<Edit> = Vec::new();
+    for edit in edits {
+        if outermost
+            .last()
+            .is_none_or(|last| last.range.end <= edit.range.start)
+        {
+            outermost.push(edit);
+        }
+    }
+
+    let mut source = source.to_owned();
+    for edit in outermost.into_iter().rev() {
+        source.replace_range(edit.range, &edit.text);
+    }
+    source
+}
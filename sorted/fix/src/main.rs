@@ -0,0 +1,119 @@
+// Rewrites `.rs` files so `#[sorted]` enums and matches are in the order the
+// `sorted` macros expect.
+//
+//     sorted-fix [--check] [--diff] [PATH...]
+//
+// Directories are searched recursively, skipping `target` and hidden
+// directories; without a path the current directory is used. `--check` does
+// not write anything and exits with status 1 if a file would change, and
+// `--diff` prints the changes as a unified diff instead of writing them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: sorted-fix [--check] [--diff] [PATH...]";
+
+fn main() -> ExitCode {
+    let mut check = false;
+    let mut diff = false;
+    let mut paths = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--diff" => diff = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("unknown option `{arg}`\n{USAGE}");
+                return ExitCode::from(2);
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+
+    let mut files = Vec::new();
+    for path in &paths {
+        if let Err(err) = collect_files(path, &mut files) {
+            eprintln!("{}: {err}", path.display());
+            return ExitCode::from(2);
+        }
+    }
+
+    let mut failed = false;
+    let mut unsorted = false;
+    for file in &files {
+        match fix_file(file, check || diff, diff) {
+            Ok(changed) => unsorted |= changed,
+            Err(err) => {
+                eprintln!("{err}");
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::from(2)
+    } else if check && unsorted {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Fix one file, returning whether it was out of order
+fn fix_file(path: &Path, dry_run: bool, diff: bool) -> Result<bool, String> {
+    let display = path.display();
+    let source = fs::read_to_string(path).map_err(|err| format!("{display}: {err}"))?;
+    let fixed = sorted_fix::fix_source(&source).map_err(|err| {
+        let start = err.span().start();
+        format!("{display}:{}:{}: {err}", start.line, start.column + 1)
+    })?;
+    if fixed == source {
+        return Ok(false);
+    }
+
+    if diff {
+        let name = display.to_string();
+        print!(
+            "{}",
+            similar::TextDiff::from_lines(&source, &fixed)
+                .unified_diff()
+                .header(&name, &name)
+        );
+    } else if dry_run {
+        println!("{display} is not sorted");
+    }
+    if !dry_run {
+        fs::write(path, fixed).map_err(|err| format!("{display}: {err}"))?;
+    }
+    Ok(true)
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_owned());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if path.is_dir() {
+            if name != "target" && !name.starts_with('.') {
+                collect_files(&path, files)?;
+            }
+        } else if name.ends_with(".rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,440 @@
+use sorted_fix::fix_source;
+
+#[test]
+fn enum_variants() {
+    let source = r#"
+#[sorted]
+pub enum Error {
+    /// Reading failed
+    Io(io::Error),
+    // kept in place
+    #[cfg(feature = "fmt")]
+    Fmt(fmt::Error),
+    Dyn(Box<dyn StdError>),
+}
+"#;
+    let expected = r#"
+#[sorted]
+pub enum Error {
+    Dyn(Box<dyn StdError>),
+    // kept in place
+    #[cfg(feature = "fmt")]
+    Fmt(fmt::Error),
+    /// Reading failed
+    Io(io::Error),
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn match_arms() {
+    let source = r#"
+#[sorted::check]
+fn f(err: &Error) -> &str {
+    #[sorted]
+    match err {
+        Error::Io(_) => {
+            "io"
+        }
+        Error::Fmt(_) => "fmt"
+    }
+}
+"#;
+    let expected = r#"
+#[sorted::check]
+fn f(err: &Error) -> &str {
+    #[sorted]
+    match err {
+        Error::Fmt(_) => "fmt",
+        Error::Io(_) => {
+            "io"
+        }
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn method() {
+    let source = r#"
+impl Display for Error {
+    #[sorted::check]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[sorted]
+        match self {
+            Io(e) => write!(f, "{}", e),
+            Fmt(e) => write!(f, "{}", e),
+        }
+    }
+}
+"#;
+    let expected = r#"
+impl Display for Error {
+    #[sorted::check]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[sorted]
+        match self {
+            Fmt(e) => write!(f, "{}", e),
+            Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn check_on_impl() {
+    let source = r#"
+#[sorted::check(order = "descending")]
+impl Method {
+    fn name(&self) -> &str {
+        #[sorted]
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+        }
+    }
+}
+"#;
+    let expected = r#"
+#[sorted::check(order = "descending")]
+impl Method {
+    fn name(&self) -> &str {
+        #[sorted]
+        match self {
+            Method::Post => "POST",
+            Method::Get => "GET",
+        }
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn nested_check_order() {
+    let source = r#"
+#[sorted::check]
+impl Method {
+    #[sorted::check(order = "descending")]
+    fn name(&self) -> &str {
+        #[sorted]
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+        }
+    }
+}
+"#;
+    let expected = r#"
+#[sorted::check]
+impl Method {
+    #[sorted::check(order = "descending")]
+    fn name(&self) -> &str {
+        #[sorted]
+        match self {
+            Method::Post => "POST",
+            Method::Get => "GET",
+        }
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn order_argument() {
+    let source = "#[sorted(order = \"natural\")]\nenum Irq { Irq10, Irq2 }\n";
+    let expected = "#[sorted(order = \"natural\")]\nenum Irq { Irq2, Irq10 }\n";
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn nested_match() {
+    let source = r#"
+#[sorted::check]
+fn f(a: A, b: B) {
+    #[sorted]
+    match a {
+        A::Y => {
+            #[sorted]
+            match b {
+                B::Q => {}
+                B::P => {}
+            }
+        }
+        A::X => {}
+    }
+}
+"#;
+    let expected = r#"
+#[sorted::check]
+fn f(a: A, b: B) {
+    #[sorted]
+    match a {
+        A::X => {}
+        A::Y => {
+            #[sorted]
+            match b {
+                B::P => {}
+                B::Q => {}
+            }
+        }
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn literal_patterns() {
+    let source = r#"
+#[sorted::check]
+fn reason(status: u16) -> &'static str {
+    #[sorted]
+    match status {
+        404 => "not found",
+        500.. => "server error",
+        200..300 => "success",
+        100 => "continue",
+        _ => "other",
+    }
+}
+"#;
+    let expected = r#"
+#[sorted::check]
+fn reason(status: u16) -> &'static str {
+    #[sorted]
+    match status {
+        100 => "continue",
+        200..300 => "success",
+        404 => "not found",
+        500.. => "server error",
+        _ => "other",
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn tuple_patterns() {
+    let source = r#"
+#[sorted::check]
+fn next(state: State, event: Event) -> State {
+    #[sorted]
+    match (state, event) {
+        (Running, Stop) => Stopped,
+        (_, Stop) => Stopped,
+        (Idle, Start) => Running,
+        _ => state,
+    }
+}
+"#;
+    let expected = r#"
+#[sorted::check]
+fn next(state: State, event: Event) -> State {
+    #[sorted]
+    match (state, event) {
+        (Idle, Start) => Running,
+        (Running, Stop) => Stopped,
+        (_, Stop) => Stopped,
+        _ => state,
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn or_patterns() {
+    let source = r#"
+#[sorted::check]
+fn f(e: E) -> u8 {
+    #[sorted]
+    match e {
+        E::A => 0,
+        E::C | E::B => 1,
+        E::F | E::D => 2,
+        e @ (E::H | E::G) => 3,
+    }
+}
+"#;
+    let expected = r#"
+#[sorted::check]
+fn f(e: E) -> u8 {
+    #[sorted]
+    match e {
+        E::A => 0,
+        E::B | E::C => 1,
+        E::D | E::F => 2,
+        e @ (E::G | E::H) => 3,
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+
+    // arms are sorted by their first alternative once the alternatives are in order
+    let source = "#[sorted::check]\nfn f(e: E) {\n    #[sorted]\n    match e {\n        E::C | E::A => {}\n        E::B => {}\n    }\n}\n";
+    let expected = "#[sorted::check]\nfn f(e: E) {\n    #[sorted]\n    match e {\n        E::A | E::C => {}\n        E::B => {}\n    }\n}\n";
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn catch_all_binding() {
+    let source = r#"
+#[sorted::check(order = "descending")]
+fn f(e: E) -> u8 {
+    #[sorted]
+    match e {
+        E::A => 0,
+        E::B => 1,
+        other => 2,
+    }
+}
+"#;
+    let expected = r#"
+#[sorted::check(order = "descending")]
+fn f(e: E) -> u8 {
+    #[sorted]
+    match e {
+        E::B => 1,
+        E::A => 0,
+        other => 2,
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn overlapping_arms() {
+    let source = r#"
+#[sorted::check]
+fn f(pair: (S, S)) -> u8 {
+    #[sorted]
+    match pair {
+        (S::B, y) if y > 3 => 0,
+        (S::B, S::A) => 1,
+        (S::A, _) => 2,
+        _ => 3,
+    }
+}
+"#;
+    let expected = r#"
+#[sorted::check]
+fn f(pair: (S, S)) -> u8 {
+    #[sorted]
+    match pair {
+        (S::A, _) => 2,
+        (S::B, y) if y > 3 => 0,
+        (S::B, S::A) => 1,
+        _ => 3,
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn pinned() {
+    let source = r#"
+#[sorted]
+pub enum Opcode {
+    Sub,
+    #[sorted(last)]
+    Unknown,
+    #[sorted(skip)]
+    Legacy,
+    Add,
+    #[sorted(first)]
+    Nop,
+}
+"#;
+    let expected = r#"
+#[sorted]
+pub enum Opcode {
+    #[sorted(first)]
+    Nop,
+    Add,
+    #[sorted(skip)]
+    Legacy,
+    Sub,
+    #[sorted(last)]
+    Unknown,
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn sections() {
+    let source = r#"
+#[sorted]
+pub enum Error {
+    NotFound,
+    Closed,
+    #[sorted(section)]
+    Overflow,
+    Eof,
+}
+"#;
+    let expected = r#"
+#[sorted]
+pub enum Error {
+    Closed,
+    NotFound,
+    #[sorted(section)]
+    Eof,
+    Overflow,
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), expected);
+}
+
+#[test]
+fn untouched() {
+    let source = r#"
+pub enum Unsorted {
+    B,
+    A,
+}
+
+fn f(x: X) {
+    #[sorted]
+    match x {
+        X::B => {}
+        X::A => {}
+    }
+}
+
+#[sorted(declared)]
+pub enum Stage {
+    Parse,
+    Codegen,
+}
+
+#[sorted::check]
+fn stage(stage: Stage) {
+    #[sorted(as_declared = Stage)]
+    match stage {
+        Stage::Parse => {}
+        Stage::Codegen => {}
+    }
+}
+
+#[sorted::check]
+fn g(n: u8) {
+    #[sorted]
+    match n {
+        ONE => {}
+        0 => {}
+        _ => {}
+    }
+}
+"#;
+    assert_eq!(fix_source(source).unwrap(), source);
+}
@@ -1,75 +1,399 @@
-use crate::check_sorting::{check_sorting, SimplifiedPath, Sortable};
+use crate::check_sorting::{check_overlaps, check_sorting};
+use crate::marker::check_sections;
 use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
+use sorted_core::{is_binding, Markers, Order, Pin, Sortable};
 use syn::spanned::Spanned;
 use syn::visit_mut::VisitMut;
 use syn::ExprMatch;
 
 #[derive(Default)]
 struct CheckSortedMatch {
+    /// The order given to `#[sorted::check(order = "...")]`, for matches that do not set their own
+    pub order: Order,
     pub sorting_errors: TokenStream,
     pub non_sortable: TokenStream,
+    pub malformed_attrs: TokenStream,
 }
 
 impl VisitMut for CheckSortedMatch {
-    fn visit_expr_match_mut(&mut self, node: &mut ExprMatch) {
+    fn visit_expr_mut(&mut self, node: &mut syn::Expr) {
+        let syn::Expr::Match(expr_match) = node else {
+            return syn::visit_mut::visit_expr_mut(self, node);
+        };
+        let assertion = self.check_match(expr_match);
+
+        // matches nested in this one, including inside closures, async blocks and nested fns,
+        // are visited too, whether or not this one is #[sorted]
+        syn::visit_mut::visit_expr_match_mut(self, expr_match);
+
+        // the assertion needs a place for its const item, so it goes in a block with the match
+        if let Some(assertion) = assertion {
+            let expr_match = std::mem::replace(node, syn::Expr::PLACEHOLDER);
+            *node = syn::parse_quote!({ #assertion #expr_match });
+        }
+    }
+
+    fn visit_item_fn_mut(&mut self, node: &mut syn::ItemFn) {
+        let order = self.take_nested_check(&mut node.attrs);
+        self.with_order(order, |this| syn::visit_mut::visit_item_fn_mut(this, node));
+    }
+
+    fn visit_item_impl_mut(&mut self, node: &mut syn::ItemImpl) {
+        let order = self.take_nested_check(&mut node.attrs);
+        self.with_order(order, |this| {
+            syn::visit_mut::visit_item_impl_mut(this, node)
+        });
+    }
+
+    fn visit_item_trait_mut(&mut self, node: &mut syn::ItemTrait) {
+        let order = self.take_nested_check(&mut node.attrs);
+        self.with_order(order, |this| {
+            syn::visit_mut::visit_item_trait_mut(this, node)
+        });
+    }
+
+    fn visit_item_mod_mut(&mut self, node: &mut syn::ItemMod) {
+        let order = self.take_nested_check(&mut node.attrs);
+        self.with_order(order, |this| syn::visit_mut::visit_item_mod_mut(this, node));
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, node: &mut syn::ImplItemFn) {
+        let order = self.take_nested_check(&mut node.attrs);
+        self.with_order(order, |this| {
+            syn::visit_mut::visit_impl_item_fn_mut(this, node)
+        });
+    }
+
+    fn visit_trait_item_fn_mut(&mut self, node: &mut syn::TraitItemFn) {
+        let order = self.take_nested_check(&mut node.attrs);
+        self.with_order(order, |this| {
+            syn::visit_mut::visit_trait_item_fn_mut(this, node)
+        });
+    }
+}
+
+impl CheckSortedMatch {
+    /// Remove the `#[sorted::check]` attribute of an item nested in the checked one, returning its
+    /// order
+    ///
+    /// The nested item is checked here with its own order, so the attribute is not expanded a
+    /// second time. A malformed attribute is left in place to report its own error.
+    fn take_nested_check(&mut self, attrs: &mut Vec<syn::Attribute>) -> Option<Order> {
+        let index = attrs.iter().position(|attr| is_check_path(attr.path()))?;
+        let mut order = Order::default();
+        let parsed = match &attrs[index].meta {
+            syn::Meta::Path(_) => Ok(()),
+            _ => attrs[index].parse_nested_meta(|meta| parse_order(&meta, &mut order)),
+        };
+        parsed.ok()?;
+        attrs.remove(index);
+        Some(order)
+    }
+
+    /// Visit a nested item with the order of its own `#[sorted::check]`, if it has one
+    fn with_order(&mut self, order: Option<Order>, visit: impl FnOnce(&mut Self)) {
+        let outer = self.order;
+        if let Some(order) = order {
+            self.order = order;
+        }
+        visit(self);
+        self.order = outer;
+    }
+
+    /// Check the order of the arms of a `#[sorted]` match, or for
+    /// `#[sorted(as_declared = ...)]`, return the assertion checking it at compile time
+    fn check_match(&mut self, node: &mut ExprMatch) -> Option<TokenStream> {
         // remove `#[sorted]` attributes, if present
-        // todo: emit error for malformed attribute
         let sorted_attrs = node
             .attrs
             .extract_if(.., |attr| attr.path().is_ident("sorted"))
-            .map(|_| ())
-            .count();
-        if sorted_attrs == 0 {
-            return;
+            .collect::<Vec<_>>();
+        if sorted_attrs.is_empty() {
+            for arm in &mut node.arms {
+                let marker = arm
+                    .attrs
+                    .extract_if(.., |attr| attr.path().is_ident("sorted"))
+                    .next();
+                if let Some(attr) = marker {
+                    self.malformed_attrs.extend(
+                        syn::Error::new(
+                            attr.span(),
+                            "`#[sorted(...)]` only applies to arms of a #[sorted] match",
+                        )
+                        .into_compile_error(),
+                    );
+                }
+            }
+            return None;
+        }
+
+        let mut order = self.order;
+        let mut as_declared = None;
+        for attr in &sorted_attrs {
+            if let Err(e) = parse_match_attr(attr, &mut order, &mut as_declared) {
+                self.malformed_attrs.extend(e.into_compile_error());
+                return None;
+            }
+        }
+
+        let mut markers = Vec::new();
+        for arm in &mut node.arms {
+            match Markers::take(&mut arm.attrs) {
+                Ok(arm_markers) => markers.push(arm_markers),
+                Err(e) => {
+                    self.malformed_attrs.extend(e.into_compile_error());
+                    return None;
+                }
+            }
+        }
+
+        if let Some(enum_path) = as_declared {
+            return match declared_order_assertion(&enum_path, &node.arms, &markers) {
+                Ok(assertion) => Some(assertion),
+                Err(e) => {
+                    self.sorting_errors.extend(e.into_compile_error());
+                    None
+                }
+            };
         }
 
+        let mut alternatives = Vec::new();
         let idents = match node
             .arms
             .iter()
-            .map(|arm| {
-                let sortable = match &arm.pat {
-                    syn::Pat::Ident(pat_ident) => Sortable::Ident(&pat_ident.ident),
-                    syn::Pat::Path(pat_path) => {
-                        Sortable::Path(SimplifiedPath::try_from(&pat_path.path)?)
-                    }
-                    syn::Pat::Struct(pat_struct) => {
-                        Sortable::Path(SimplifiedPath::try_from(&pat_struct.path)?)
-                    }
-                    syn::Pat::TupleStruct(tuple_struct) => {
-                        Sortable::Path(SimplifiedPath::try_from(&tuple_struct.path)?)
-                    }
-                    syn::Pat::Wild(pat_wild) => Sortable::Wildcard(&pat_wild.underscore_token),
-                    _ => return Err(syn::Error::new(arm.pat.span(), "unsupported by #[sorted]")),
-                };
-                Ok(sortable)
-            })
-            .collect::<Result<Vec<Sortable>, _>>()
+            .map(|arm| Sortable::from_pat(&arm.pat, &mut alternatives))
+            .collect::<syn::Result<Vec<_>>>()
         {
             Ok(idents) => idents,
             Err(e) => {
                 self.non_sortable.extend(e.into_compile_error());
-                return;
+                return None;
             }
         };
+        for cases in alternatives {
+            if let Some(e) = check_sorting(cases, order) {
+                self.sorting_errors.extend(e);
+            }
+        }
 
-        if let Some(e) = check_sorting(idents) {
+        // arms with a guard may overlap with later ones on purpose
+        let unguarded = node
+            .arms
+            .iter()
+            .zip(&idents)
+            .filter(|(arm, _)| arm.guard.is_none())
+            .map(|(_, sortable)| sortable.clone())
+            .collect::<Vec<_>>();
+        if let Some(e) = check_sections(idents.into_iter().zip(markers), order) {
             self.sorting_errors.extend(e);
         }
+        if let Some(e) = check_overlaps(unguarded) {
+            self.sorting_errors.extend(e);
+        }
+        None
+    }
+}
+
+/// Parse `#[sorted]`, `#[sorted(order = "...")]` or `#[sorted(as_declared = EnumPath)]` on a
+/// match expression
+fn parse_match_attr(
+    attr: &syn::Attribute,
+    order: &mut Order,
+    as_declared: &mut Option<syn::Path>,
+) -> syn::Result<()> {
+    match &attr.meta {
+        syn::Meta::Path(_) => Ok(()),
+        syn::Meta::List(_) => attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("as_declared") {
+                *as_declared = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("order") {
+                parse_order(&meta, order)
+            } else {
+                Err(meta.error("expected `order = \"...\"` or `as_declared = EnumPath`"))
+            }
+        }),
+        syn::Meta::NameValue(name_value) => Err(syn::Error::new(
+            name_value.eq_token.span,
+            "expected `#[sorted]` or `#[sorted(order = \"...\")]`",
+        )),
     }
 }
 
-pub fn execute(_args: TokenStream, mut input: syn::Item) -> syn::Result<TokenStream> {
-    let syn::Item::Fn(ref mut item_fn) = input else {
-        return Err(syn::Error::new(Span::call_site(), "expected function"));
+/// Produce a const item that fails to evaluate if the variants named by the arms are not in the
+/// order they are declared in, as listed by `#[sorted(declared)]` on the enum
+///
+/// Arms marked `#[sorted(skip)]` are left out. Each variant is looked up in the declared list and
+/// compared with the one named before it, with the panic spanned to its pattern so the error
+/// points there.
+fn declared_order_assertion(
+    enum_path: &syn::Path,
+    arms: &[syn::Arm],
+    markers: &[Markers],
+) -> syn::Result<TokenStream> {
+    let mut names = Vec::new();
+    for (arm, markers) in arms.iter().zip(markers) {
+        if markers.pin != Pin::Skip {
+            variant_names(
+                &arm.pat,
+                &enum_path.segments.last().unwrap().ident,
+                &mut names,
+            )?;
+        }
+    }
+
+    let enum_name = enum_path.to_token_stream().to_string().replace(' ', "");
+    let lookups = names.iter().map(|ident| {
+        let name = ident.to_string();
+        let message = format!("{name} is not a variant of {enum_name}");
+        quote::quote_spanned! {ident.span()=>
+            if position(#name) == usize::MAX {
+                ::core::panic!(#message);
+            }
+        }
+    });
+    let comparisons = names.windows(2).map(|pair| {
+        let (previous, name) = (pair[0].to_string(), pair[1].to_string());
+        let message = format!(
+            "{name} should sort before {previous} to match the declaration order of {enum_name}"
+        );
+        quote::quote_spanned! {pair[1].span()=>
+            if position(#name) < position(#previous) {
+                ::core::panic!(#message);
+            }
+        }
+    });
+
+    Ok(quote::quote! {
+        const _: () = {
+            const DECLARED: &[&str] = #enum_path;
+
+            const fn position(name: &str) -> usize {
+                let name = name.as_bytes();
+                let mut i = 0;
+                while i < DECLARED.len() {
+                    let declared = DECLARED[i].as_bytes();
+                    if declared.len() == name.len() {
+                        let mut j = 0;
+                        while j < name.len() && declared[j] == name[j] {
+                            j += 1;
+                        }
+                        if j == name.len() {
+                            return i;
+                        }
+                    }
+                    i += 1;
+                }
+                usize::MAX
+            }
+
+            #(#lookups)*
+            #(#comparisons)*
+        };
+    })
+}
+
+/// Collect the names of the variants a pattern matches, the last segment of each path, including
+/// every alternative of an or-pattern
+///
+/// Wildcards and plain bindings like `other =>` match any variant, so they name none. Paths of
+/// more than one segment must name the variant through `enum_name`, or `Self`.
+fn variant_names<'ast>(
+    pat: &'ast syn::Pat,
+    enum_name: &syn::Ident,
+    names: &mut Vec<&'ast syn::Ident>,
+) -> syn::Result<()> {
+    let path = match pat {
+        syn::Pat::Ident(syn::PatIdent {
+            subpat: Some((_, subpat)),
+            ..
+        }) => return variant_names(subpat, enum_name, names),
+        syn::Pat::Ident(pat_ident) if !is_binding(&pat_ident.ident) => {
+            names.push(&pat_ident.ident);
+            return Ok(());
+        }
+        syn::Pat::Path(pat_path) => &pat_path.path,
+        syn::Pat::Struct(pat_struct) => &pat_struct.path,
+        syn::Pat::TupleStruct(tuple_struct) => &tuple_struct.path,
+        syn::Pat::Reference(pat_reference) => {
+            return variant_names(&pat_reference.pat, enum_name, names)
+        }
+        syn::Pat::Paren(pat_paren) => return variant_names(&pat_paren.pat, enum_name, names),
+        syn::Pat::Or(pat_or) => {
+            for case in &pat_or.cases {
+                variant_names(case, enum_name, names)?;
+            }
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+
+    let mut segments = path.segments.iter().rev();
+    let Some(variant) = segments.next() else {
+        return Ok(());
     };
+    if let Some(parent) = segments.next() {
+        if parent.ident != *enum_name && parent.ident != "Self" {
+            return Err(syn::Error::new(
+                path.span(),
+                format!(
+                    "{} is not a variant of {enum_name}",
+                    path.to_token_stream().to_string().replace(' ', ""),
+                ),
+            ));
+        }
+    }
+    names.push(&variant.ident);
+    Ok(())
+}
+
+/// Whether `path` is `sorted::check`
+fn is_check_path(path: &syn::Path) -> bool {
+    path.segments.len() == 2
+        && path.segments[0].ident == "sorted"
+        && path.segments[1].ident == "check"
+}
+
+fn parse_order(meta: &syn::meta::ParseNestedMeta, order: &mut Order) -> syn::Result<()> {
+    if meta.path.is_ident("order") {
+        *order = Order::parse(meta)?;
+        Ok(())
+    } else {
+        Err(meta.error("expected `order = \"...\"`"))
+    }
+}
+
+pub fn execute(args: TokenStream, mut input: syn::Item) -> syn::Result<TokenStream> {
+    let mut order = Order::default();
+    let parser = syn::meta::parser(|meta| parse_order(&meta, &mut order));
+    syn::parse::Parser::parse2(parser, args)?;
 
-    let mut checker = CheckSortedMatch::default();
-    syn::visit_mut::visit_item_fn_mut(&mut checker, item_fn);
+    let mut checker = CheckSortedMatch {
+        order,
+        ..CheckSortedMatch::default()
+    };
+    match &mut input {
+        syn::Item::Fn(item_fn) => syn::visit_mut::visit_item_fn_mut(&mut checker, item_fn),
+        syn::Item::Impl(item_impl) => syn::visit_mut::visit_item_impl_mut(&mut checker, item_impl),
+        syn::Item::Trait(item_trait) => {
+            syn::visit_mut::visit_item_trait_mut(&mut checker, item_trait);
+        }
+        syn::Item::Mod(item_mod) if item_mod.content.is_some() => {
+            syn::visit_mut::visit_item_mod_mut(&mut checker, item_mod);
+        }
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "expected function, impl block, trait or inline module",
+            ));
+        }
+    }
 
     let mut stream = input.into_token_stream();
     stream.extend(checker.sorting_errors);
     stream.extend(checker.non_sortable);
+    stream.extend(checker.malformed_attrs);
 
     Ok(stream)
 }
@@ -1,145 +1,182 @@
-use proc_macro2::{Span, TokenStream};
-use std::cell::RefCell;
-use std::cmp::Ordering;
+use proc_macro2::TokenStream;
+use sorted_core::{check_kinds, Kind, Order, Sortable, Value, ValuePattern};
 use std::fmt::{Display, Formatter};
-use std::rc::Rc;
-use syn::spanned::Spanned;
 
-pub struct SimplifiedPath<'ast> {
-    pub source: &'ast syn::Path,
-    pub stringified: String,
+/// Where an out-of-order element should be moved to
+pub enum Placement<'a, 'ast> {
+    Before(&'a Sortable<'ast>),
+    /// Only for elements greater than everything that stays in place
+    After(&'a Sortable<'ast>),
 }
 
-impl<'ast> TryFrom<&'ast syn::Path> for SimplifiedPath<'ast> {
-    type Error = syn::Error;
-    fn try_from(path: &'ast syn::Path) -> Result<Self, syn::Error> {
-        if path.leading_colon.is_some() {
-            return Err(syn::Error::new(
-                path.leading_colon.span(),
-                "unsupported by #[sorted]",
-            ));
-        }
-        let mut stringified = String::new();
-        for segment in &path.segments {
-            if !matches!(segment.arguments, syn::PathArguments::None) {
-                return Err(syn::Error::new(
-                    segment.arguments.span(),
-                    "unsupported by #[sorted]",
-                ));
-            }
-
-            stringified = if stringified.is_empty() {
-                segment.ident.to_string()
-            } else {
-                format!("{stringified}::{}", segment.ident)
-            };
+impl Display for Placement<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Placement::Before(sortable) => write!(f, "before {sortable}"),
+            Placement::After(sortable) => write!(f, "after {sortable}"),
         }
-
-        Ok(Self {
-            source: path,
-            stringified,
-        })
     }
 }
 
-pub enum Sortable<'ast> {
-    Ident(&'ast syn::Ident),
-    Path(SimplifiedPath<'ast>),
-    Wildcard(&'ast syn::Token![_]),
-}
-
-impl Sortable<'_> {
-    pub fn span(&self) -> Span {
-        match self {
-            Sortable::Ident(ident) => ident.span(),
-            Sortable::Path(path) => path.source.span(),
-            Sortable::Wildcard(wildcard) => wildcard.span,
-        }
+/// Check that the elements are in order, and report the fewest elements that need to move to
+/// make them so
+///
+/// The elements that stay in place are a longest non-decreasing subsequence, found in
+/// O(n log n). When there are several, the one keeping the earliest elements is used, so the
+/// elements reported are the ones written later.
+pub fn check_sorting<'ast>(
+    idents: impl IntoIterator<Item = Sortable<'ast>>,
+    order: Order,
+) -> Option<TokenStream> {
+    let elements = idents.into_iter().collect::<Vec<_>>();
+    if let Some(error) = check_kinds(&elements.iter().collect::<Vec<_>>()) {
+        return Some(error.into_compile_error());
     }
-}
+    let compare = |a: usize, b: usize| elements[a].compare(&elements[b], order);
 
-impl PartialEq for Sortable<'_> {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Self::Ident(a), Self::Ident(b)) => a.eq(b),
-            (Self::Path(a), Self::Path(b)) => a.stringified.eq(&b.stringified),
-            (Self::Wildcard(_), Self::Wildcard(_)) => true,
-            _ => false,
+    // `run_len[i]` is the length of the longest non-decreasing run starting at `i`, found from
+    // the right where `heads[k]` is the greatest first element of such a run of length `k + 1`
+    let mut run_len = vec![0; elements.len()];
+    let mut heads: Vec<usize> = Vec::new();
+    for i in (0..elements.len()).rev() {
+        let k = heads.partition_point(|&head| compare(head, i).is_ge());
+        if k == heads.len() {
+            heads.push(i);
+        } else {
+            heads[k] = i;
         }
+        run_len[i] = k + 1;
     }
-}
 
-impl PartialOrd for Sortable<'_> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match (self, other) {
-            (Self::Ident(a), Self::Ident(b)) => a.partial_cmp(b),
-            (Self::Path(a), Self::Path(b)) => a.stringified.partial_cmp(&b.stringified),
-            (Self::Ident(ident), Self::Path(path)) => {
-                ident.to_string().partial_cmp(&path.stringified)
-            }
-            (Self::Path(path), Self::Ident(ident)) => {
-                path.stringified.partial_cmp(&ident.to_string())
-            }
-            (Self::Wildcard(_), Self::Wildcard(_)) => Some(Ordering::Equal),
-            (Self::Wildcard(_), _) => Some(Ordering::Greater),
-            (_, Self::Wildcard(_)) => Some(Ordering::Less),
+    // walk the longest run from the left, always taking the earliest element that continues it
+    let mut kept = Vec::with_capacity(heads.len());
+    let mut remaining = heads.len();
+    for (i, &len) in run_len.iter().enumerate() {
+        if remaining > 0
+            && len == remaining
+            && kept.last().is_none_or(|&last| compare(last, i).is_le())
+        {
+            kept.push(i);
+            remaining -= 1;
         }
     }
-}
+    if kept.len() == elements.len() {
+        return None;
+    }
 
-impl Display for Sortable<'_> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Ident(ident) => ident.fmt(f),
-            Sortable::Path(path) => path.stringified.fmt(f),
-            Sortable::Wildcard(_) => "wildcard".fmt(f),
-        }
+    let mut is_kept = vec![false; elements.len()];
+    for &i in &kept {
+        is_kept[i] = true;
     }
+    let misplaced = (0..elements.len())
+        .filter(|&i| !is_kept[i])
+        .map(|i| {
+            let position = kept.partition_point(|&k| compare(k, i).is_le());
+            let placement = match kept.get(position) {
+                Some(&before) => Placement::Before(&elements[before]),
+                None => Placement::After(&elements[kept[kept.len() - 1]]),
+            };
+            (&elements[i], placement)
+        })
+        .collect::<Vec<_>>();
+
+    let mut expected = elements.iter().collect::<Vec<_>>();
+    expected.sort_by(|a, b| a.compare(b, order));
+    let expected = expected
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Some(report(&misplaced, &expected))
 }
 
-pub fn check_sorting<'ast>(
-    idents: impl IntoIterator<Item = Sortable<'ast>>,
+/// Report literal and range patterns matching values already matched by an earlier one, which
+/// makes them partly or entirely unreachable
+pub fn check_overlaps<'ast>(
+    patterns: impl IntoIterator<Item = Sortable<'ast>>,
 ) -> Option<TokenStream> {
-    // initialize a map of each variant to the variant it should be sorted before
-    // we'll populate it below, but each variant starts with `None`
-    // Vec<(Rc<Sortable>, RefCell<Option<Rc<Sortable>>>)>
-    let map = idents
-        .into_iter()
-        .map(|sortable| {
-            let sortable = Rc::new(sortable);
-            (sortable, RefCell::new(None))
+    let patterns = patterns.into_iter().collect::<Vec<_>>();
+    let mut ranges = patterns
+        .iter()
+        .enumerate()
+        .filter_map(|(i, sortable)| match sortable {
+            Sortable::Value(ValuePattern {
+                value: Value::Number { kind, low, high },
+                ..
+            }) => Some((*kind, *low, *high, i)),
+            _ => None,
         })
         .collect::<Vec<_>>();
+    ranges.sort_unstable();
 
-    // O(n^2) but who cares
-    for (i, (a, _)) in map.iter().enumerate() {
-        for (b, goes_before) in &map[i + 1..] {
-            if a > b {
-                let mut goes_before = goes_before.borrow_mut();
-                // set `b_goes_before` if not set already
-                // only override it if `a` is lexicographically before the existing value
-                if goes_before.is_none()
-                    || goes_before
-                        .as_ref()
-                        .is_some_and(|goes_before| a < goes_before)
-                {
-                    *goes_before = Some(Rc::clone(a));
+    // going by where they start, each range overlaps the one reaching furthest before it if it
+    // starts before that one ends
+    let mut errors = TokenStream::new();
+    let mut furthest: Option<(Kind, i128, usize)> = None;
+    for &(kind, low, high, i) in &ranges {
+        match furthest {
+            Some((furthest_kind, furthest_high, furthest_i))
+                if furthest_kind == kind && low <= furthest_high =>
+            {
+                let (earlier, later) = (i.min(furthest_i), i.max(furthest_i));
+                errors.extend(
+                    syn::Error::new(
+                        patterns[later].span(),
+                        format!("{} overlaps {}", patterns[later], patterns[earlier]),
+                    )
+                    .into_compile_error(),
+                );
+                if high > furthest_high {
+                    furthest = Some((kind, high, i));
                 }
             }
+            _ => furthest = Some((kind, high, i)),
+        }
+    }
+    if errors.is_empty() {
+        None
+    } else {
+        Some(errors)
+    }
+}
+
+/// Generate an error for each out-of-order element, the first one also listing the expected
+/// order, and combine them into one token stream
+#[cfg(not(feature = "nightly"))]
+fn report(misplaced: &[(&Sortable, Placement)], expected: &str) -> TokenStream {
+    let mut stream = TokenStream::new();
+    for (i, (sortable, placement)) in misplaced.iter().enumerate() {
+        let mut message = format!("{sortable} should sort {placement}");
+        if i == 0 {
+            message.push_str(&format!("\nexpected order: {expected}"));
         }
+        stream.extend(syn::Error::new(sortable.span(), message).into_compile_error());
     }
+    stream
+}
 
-    // generate errors for any out-of-order ident and combine them into one token stream
-    map.into_iter()
-        .filter_map(|(b, goes_before)| {
-            let a = goes_before.into_inner()?;
-            Some(
-                syn::Error::new(b.span(), format!("{b} should sort before {a}"))
-                    .into_compile_error(),
-            )
-        })
-        .reduce(|mut stream, error| {
-            stream.extend(error);
-            stream
-        })
+/// Emit an error for each out-of-order element, with a help note pointing at where it should be
+/// moved to, the first one also noting the expected order
+#[cfg(feature = "nightly")]
+fn report(misplaced: &[(&Sortable, Placement)], expected: &str) -> TokenStream {
+    use proc_macro::{Diagnostic, Level};
+
+    for (i, (sortable, placement)) in misplaced.iter().enumerate() {
+        let (Placement::Before(target) | Placement::After(target)) = placement;
+        let mut diagnostic = Diagnostic::spanned(
+            sortable.span().unwrap(),
+            Level::Error,
+            format!("{sortable} should sort {placement}"),
+        )
+        .span_help(
+            target.span().unwrap(),
+            format!("move {sortable} {placement}"),
+        );
+        if i == 0 {
+            diagnostic = diagnostic.note(format!("expected order: {expected}"));
+        }
+        diagnostic.emit();
+    }
+    TokenStream::new()
 }
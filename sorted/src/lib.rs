@@ -1,5 +1,8 @@
+#![cfg_attr(feature = "nightly", feature(proc_macro_diagnostic))]
+
 mod check;
 mod check_sorting;
+mod marker;
 mod sorted;
 
 use proc_macro::TokenStream;
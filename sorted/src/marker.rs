@@ -0,0 +1,75 @@
+use crate::check_sorting::check_sorting;
+use proc_macro2::TokenStream;
+use sorted_core::{Markers, Order, Pin, Sortable};
+
+/// Check each section on its own with [`check_pinned`], so pinning an element first or last
+/// puts it at the start or end of its section
+pub fn check_sections<'ast>(
+    elements: impl IntoIterator<Item = (Sortable<'ast>, Markers)>,
+    order: Order,
+) -> Option<TokenStream> {
+    let mut sections: Vec<Vec<(Sortable, Pin)>> = vec![Vec::new()];
+    for (sortable, markers) in elements {
+        if markers.section.is_some() {
+            sections.push(Vec::new());
+        }
+        sections.last_mut().unwrap().push((sortable, markers.pin));
+    }
+
+    let errors = sections
+        .into_iter()
+        .filter_map(|section| check_pinned(section, order))
+        .collect::<TokenStream>();
+    if errors.is_empty() {
+        None
+    } else {
+        Some(errors)
+    }
+}
+
+/// Check that the elements pinned first or last are there, and that the others are sorted
+///
+/// Elements pinned first or last can be in any order among themselves, and wildcards can go
+/// before or after the elements pinned last.
+fn check_pinned<'ast>(
+    elements: impl IntoIterator<Item = (Sortable<'ast>, Pin)>,
+    order: Order,
+) -> Option<TokenStream> {
+    let elements = elements
+        .into_iter()
+        .filter(|(_, pin)| *pin != Pin::Skip)
+        .collect::<Vec<_>>();
+
+    let mut errors = TokenStream::new();
+    let first_unpinned = elements.iter().position(|(_, pin)| *pin != Pin::First);
+    let last_unpinned = elements
+        .iter()
+        .rposition(|(sortable, pin)| *pin != Pin::Last && !sortable.is_wildcard());
+    for (i, (sortable, pin)) in elements.iter().enumerate() {
+        let message = match (pin, first_unpinned, last_unpinned) {
+            (Pin::First, Some(other), _) if other < i => {
+                let (other, _) = &elements[other];
+                format!("{sortable} is pinned first but comes after {other}")
+            }
+            (Pin::Last, _, Some(other)) if other > i => {
+                let (other, _) = &elements[other];
+                format!("{sortable} is pinned last but comes before {other}")
+            }
+            _ => continue,
+        };
+        errors.extend(syn::Error::new(sortable.span(), message).into_compile_error());
+    }
+
+    errors.extend(check_sorting(
+        elements
+            .into_iter()
+            .filter(|(_, pin)| *pin == Pin::Sorted)
+            .map(|(sortable, _)| sortable),
+        order,
+    ));
+    if errors.is_empty() {
+        None
+    } else {
+        Some(errors)
+    }
+}
@@ -1,19 +1,289 @@
-use crate::check_sorting::{check_sorting, Sortable};
+use crate::check_sorting::check_sorting;
+use crate::marker::check_sections;
 use proc_macro2::{Span, TokenStream};
-use quote::ToTokens;
+use quote::{quote, ToTokens};
+use sorted_core::{Markers, Order, Sortable};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
 
-pub fn execute(_args: TokenStream, input: syn::Item) -> syn::Result<TokenStream> {
-    let syn::Item::Enum(ref item_enum) = input else {
-        return Err(syn::Error::new(
-            Span::call_site(),
-            "expected enum or match expression",
-        ));
-    };
+/// Arguments of `#[sorted(...)]` on an item
+#[derive(Default)]
+struct SortedArgs {
+    /// `#[sorted(declared)]`, instead of checking the order of the variants, list them in a
+    /// hidden const for matches with `#[sorted(as_declared = ...)]` to follow
+    declared: Option<syn::Path>,
+    /// `#[sorted(fields)]`, also check the named fields of each enum variant
+    fields: Option<syn::Path>,
+    /// `#[sorted(group_by_kind)]`, require consts, then types, then fns in impls, traits and
+    /// modules, each sorted on their own
+    group_by_kind: Option<syn::Path>,
+    /// `#[sorted(order = "...")]`
+    order: Order,
+}
+
+fn parse_args(args: TokenStream) -> syn::Result<SortedArgs> {
+    let mut sorted_args = SortedArgs::default();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("declared") {
+            sorted_args.declared = Some(meta.path);
+            Ok(())
+        } else if meta.path.is_ident("fields") {
+            sorted_args.fields = Some(meta.path);
+            Ok(())
+        } else if meta.path.is_ident("group_by_kind") {
+            sorted_args.group_by_kind = Some(meta.path);
+            Ok(())
+        } else if meta.path.is_ident("order") {
+            sorted_args.order = Order::parse(&meta)?;
+            Ok(())
+        } else {
+            Err(meta.error("expected `declared`, `fields`, `group_by_kind` or `order = \"...\"`"))
+        }
+    });
+    syn::parse::Parser::parse2(parser, args)?;
+    Ok(sorted_args)
+}
 
-    let errors = check_sorting(item_enum.variants.iter().map(|v| Sortable::Ident(&v.ident)));
+pub fn execute(args: TokenStream, mut input: syn::Item) -> syn::Result<TokenStream> {
+    let args = parse_args(args)?;
+
+    // the markers are removed from the output before the variants are borrowed for checking
+    let mut markers = Vec::new();
+    if let syn::Item::Enum(item_enum) = &mut input {
+        for variant in &mut item_enum.variants {
+            markers.push(Markers::take(&mut variant.attrs)?);
+        }
+    }
+
+    let mut errors = TokenStream::new();
+    match &input {
+        syn::Item::Enum(item_enum) => {
+            reject_arg(&args.group_by_kind, "impls, traits and modules")?;
+            if args.declared.is_some() {
+                errors.extend(declared_variants(item_enum));
+            } else {
+                errors.extend(check_sections(
+                    item_enum
+                        .variants
+                        .iter()
+                        .map(|v| Sortable::Ident(&v.ident))
+                        .zip(markers),
+                    args.order,
+                ));
+            }
+            if args.fields.is_some() {
+                for variant in &item_enum.variants {
+                    if let syn::Fields::Named(fields) = &variant.fields {
+                        errors.extend(check_named_fields(&fields.named, args.order));
+                    }
+                }
+            }
+        }
+        syn::Item::Struct(item_struct) => {
+            reject_arg(&args.declared, "enums")?;
+            reject_arg(&args.fields, "enums")?;
+            reject_arg(&args.group_by_kind, "impls, traits and modules")?;
+            let syn::Fields::Named(fields) = &item_struct.fields else {
+                return Err(syn::Error::new(
+                    item_struct.fields.span(),
+                    "#[sorted] requires a struct with named fields",
+                ));
+            };
+            errors.extend(check_named_fields(&fields.named, args.order));
+        }
+        syn::Item::Union(item_union) => {
+            reject_arg(&args.declared, "enums")?;
+            reject_arg(&args.fields, "enums")?;
+            reject_arg(&args.group_by_kind, "impls, traits and modules")?;
+            errors.extend(check_named_fields(&item_union.fields.named, args.order));
+        }
+        syn::Item::Impl(item_impl) => {
+            reject_arg(&args.declared, "enums")?;
+            reject_arg(&args.fields, "enums")?;
+            let items = item_impl.items.iter().filter_map(NamedItem::from_impl_item);
+            errors.extend(check_items(items, &args));
+        }
+        syn::Item::Trait(item_trait) => {
+            reject_arg(&args.declared, "enums")?;
+            reject_arg(&args.fields, "enums")?;
+            let items = item_trait
+                .items
+                .iter()
+                .filter_map(NamedItem::from_trait_item);
+            errors.extend(check_items(items, &args));
+        }
+        syn::Item::Mod(item_mod) => {
+            reject_arg(&args.declared, "enums")?;
+            reject_arg(&args.fields, "enums")?;
+            let Some((_, content)) = &item_mod.content else {
+                return Err(syn::Error::new(
+                    item_mod.semi.span(),
+                    "#[sorted] requires an inline module",
+                ));
+            };
+            let items = content.iter().filter_map(NamedItem::from_item);
+            errors.extend(check_items(items, &args));
+        }
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "expected enum, struct, union, impl, trait, mod or match expression",
+            ));
+        }
+    }
 
     let mut stream = input.into_token_stream();
-    stream.extend(errors.into_iter().flatten());
+    stream.extend(errors);
 
     Ok(stream)
 }
+
+/// List the names of the variants, in the order they are declared, in a hidden const
+///
+/// Enums only take a name in the type namespace, so the const is named after the enum. It goes
+/// wherever the enum is imported, and needs no type arguments for generic enums.
+fn declared_variants(item_enum: &syn::ItemEnum) -> TokenStream {
+    let vis = &item_enum.vis;
+    let ident = &item_enum.ident;
+    let names = item_enum.variants.iter().map(|v| v.ident.to_string());
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        #vis const #ident: &[&str] = &[#(#names),*];
+    }
+}
+
+fn check_named_fields(
+    fields: &Punctuated<syn::Field, syn::Token![,]>,
+    order: Order,
+) -> Option<TokenStream> {
+    check_sorting(
+        fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref().map(Sortable::Ident)),
+        order,
+    )
+}
+
+/// Error on an argument that was given for an item it has no meaning on
+fn reject_arg(arg: &Option<syn::Path>, applies_to: &str) -> syn::Result<()> {
+    match arg {
+        Some(arg) => Err(syn::Error::new(
+            arg.span(),
+            format!("`{}` only applies to {applies_to}", arg.to_token_stream()),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The kinds of items `#[sorted(group_by_kind)]` groups together, in their required order
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ItemKind {
+    /// `const` and `static`
+    Const,
+    /// `type`, `struct`, `enum`, `union` and `trait`
+    Type,
+    /// `fn`
+    Fn,
+}
+
+impl ItemKind {
+    fn keyword(self) -> &'static str {
+        match self {
+            ItemKind::Const => "const",
+            ItemKind::Type => "type",
+            ItemKind::Fn => "fn",
+        }
+    }
+}
+
+/// An item of an impl, trait or module that is checked by `#[sorted]`
+///
+/// Items without a name, like `use` declarations, impls and macro invocations, as well as
+/// nested modules, can go anywhere.
+struct NamedItem<'ast> {
+    kind: ItemKind,
+    ident: &'ast syn::Ident,
+}
+
+impl<'ast> NamedItem<'ast> {
+    fn from_impl_item(item: &'ast syn::ImplItem) -> Option<Self> {
+        let (kind, ident) = match item {
+            syn::ImplItem::Const(item) => (ItemKind::Const, &item.ident),
+            syn::ImplItem::Type(item) => (ItemKind::Type, &item.ident),
+            syn::ImplItem::Fn(item) => (ItemKind::Fn, &item.sig.ident),
+            _ => return None,
+        };
+        Some(Self { kind, ident })
+    }
+
+    fn from_trait_item(item: &'ast syn::TraitItem) -> Option<Self> {
+        let (kind, ident) = match item {
+            syn::TraitItem::Const(item) => (ItemKind::Const, &item.ident),
+            syn::TraitItem::Type(item) => (ItemKind::Type, &item.ident),
+            syn::TraitItem::Fn(item) => (ItemKind::Fn, &item.sig.ident),
+            _ => return None,
+        };
+        Some(Self { kind, ident })
+    }
+
+    fn from_item(item: &'ast syn::Item) -> Option<Self> {
+        let (kind, ident) = match item {
+            syn::Item::Const(item) => (ItemKind::Const, &item.ident),
+            syn::Item::Static(item) => (ItemKind::Const, &item.ident),
+            syn::Item::Type(item) => (ItemKind::Type, &item.ident),
+            syn::Item::Struct(item) => (ItemKind::Type, &item.ident),
+            syn::Item::Enum(item) => (ItemKind::Type, &item.ident),
+            syn::Item::Union(item) => (ItemKind::Type, &item.ident),
+            syn::Item::Trait(item) => (ItemKind::Type, &item.ident),
+            syn::Item::TraitAlias(item) => (ItemKind::Type, &item.ident),
+            syn::Item::Fn(item) => (ItemKind::Fn, &item.sig.ident),
+            _ => return None,
+        };
+        Some(Self { kind, ident })
+    }
+}
+
+/// Check that items are sorted by name, or with `#[sorted(group_by_kind)]`, that they are grouped
+/// by kind and sorted by name within each kind
+fn check_items<'ast>(
+    items: impl Iterator<Item = NamedItem<'ast>>,
+    args: &SortedArgs,
+) -> TokenStream {
+    let items = items.collect::<Vec<_>>();
+    if args.group_by_kind.is_none() {
+        return check_sorting(
+            items.iter().map(|item| Sortable::Ident(item.ident)),
+            args.order,
+        )
+        .unwrap_or_default();
+    }
+
+    let mut errors = TokenStream::new();
+    let mut last: Option<&NamedItem> = None;
+    for item in &items {
+        match last {
+            Some(last) if item.kind < last.kind => {
+                let message = format!(
+                    "{} {} should sort before {} {}",
+                    item.kind.keyword(),
+                    item.ident,
+                    last.kind.keyword(),
+                    last.ident,
+                );
+                errors.extend(syn::Error::new(item.ident.span(), message).into_compile_error());
+            }
+            _ => last = Some(item),
+        }
+    }
+    for kind in [ItemKind::Const, ItemKind::Type, ItemKind::Fn] {
+        errors.extend(check_sorting(
+            items
+                .iter()
+                .filter(|item| item.kind == kind)
+                .map(|item| Sortable::Ident(item.ident)),
+            args.order,
+        ));
+    }
+    errors
+}
@@ -1,8 +1,8 @@
-// The #[sorted] macro is only defined to work on enum types, so this is a test
-// to ensure that when it's attached to a struct (or anything else) it produces
-// some reasonable error. Your macro will need to look into the syn::Item that
-// it parsed to ensure that it represents an enum, returning an error for any
-// other type of Item such as a struct.
+// The #[sorted] macro is only defined to work on enum types and on types with
+// named fields, so this is a test to ensure that when it's attached to a type
+// alias (or anything else) it produces some reasonable error. Your macro will
+// need to look into the syn::Item that it parsed to ensure that it represents
+// an enum, returning an error for any other type of Item such as a type alias.
 //
 // This is an exercise in exploring how to return errors from procedural macros.
 // The goal is to produce an understandable error message which is tailored to
@@ -29,10 +29,7 @@
 use sorted::sorted;
 
 #[sorted]
-pub struct Error {
-    kind: ErrorKind,
-    message: String,
-}
+pub type Error = (ErrorKind, String);
 
 enum ErrorKind {
     Io,
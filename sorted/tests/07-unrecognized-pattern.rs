@@ -1,18 +1,17 @@
 // The macro won't need to define what it means for other sorts of patterns to
 // be sorted. It should be fine to trigger an error if any of the patterns is
-// not something that can be compared by path.
+// not something that can be compared by path or by value.
 //
 // Be sure that the resulting error message is understandable and placed
 // correctly underlining the unsupported pattern.
 
 #[sorted::check]
-fn f(bytes: &[u8]) -> Option<u8> {
+fn f(ratio: f64) -> &'static str {
     #[sorted]
-    match bytes {
-        [] => Some(0),
-        [a] => Some(*a),
-        [a, b] => Some(a + b),
-        _other => None,
+    match ratio {
+        0.0 => "none",
+        1.0 => "all",
+        _other => "some",
     }
 }
 
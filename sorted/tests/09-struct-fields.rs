@@ -0,0 +1,22 @@
+// Structs and unions with named fields can be #[sorted] too, which keeps large
+// configuration and serialization types diff-friendly. Fields are compared by
+// name exactly like enum variants.
+
+use sorted::sorted;
+
+#[sorted]
+pub struct Config {
+    address: String,
+    port: u16,
+    log_level: u8,
+    timeout: u64,
+}
+
+#[sorted]
+pub union Value {
+    int: i64,
+    float: f64,
+    bool: bool,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// #[sorted(fields)] on an enum additionally checks the named fields inside
+// each struct-like variant. Tuple and unit variants have no field names and are
+// left alone.
+
+use sorted::sorted;
+
+#[sorted(fields)]
+pub enum Event {
+    Click { x: i32, y: i32 },
+    Key(char),
+    Resize { width: u32, height: u32 },
+    Quit,
+}
+
+fn main() {}
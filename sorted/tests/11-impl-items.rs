@@ -0,0 +1,33 @@
+// #[sorted] also applies to impl blocks, traits and inline modules, where it
+// checks that the items are in order by name. Items without a name, such as
+// `use` declarations and macro invocations, are not checked.
+
+use sorted::sorted;
+
+pub struct Parser;
+
+#[sorted]
+impl Parser {
+    pub fn advance(&mut self) {}
+    pub fn peek(&self) {}
+    pub fn expect(&mut self) {}
+    pub fn parse(&mut self) {}
+}
+
+#[sorted]
+pub trait Visitor {
+    type Output;
+    fn visit_expr(&mut self);
+    fn visit_block(&mut self);
+}
+
+#[sorted]
+mod tokens {
+    use std::fmt;
+
+    pub struct Literal;
+    pub struct Ident;
+    pub fn display(_: &dyn fmt::Display) {}
+}
+
+fn main() {}
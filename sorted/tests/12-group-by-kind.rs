@@ -0,0 +1,27 @@
+// With #[sorted(group_by_kind)], consts come first, then types, then fns, and
+// each of these groups is sorted on its own.
+
+use sorted::sorted;
+
+pub struct Buffer;
+
+#[sorted(group_by_kind)]
+impl Buffer {
+    const CAPACITY: usize = 64;
+    const ALIGN: usize = 8;
+
+    pub fn clear(&mut self) {}
+    pub fn len(&self) -> usize {
+        0
+    }
+}
+
+#[sorted(group_by_kind)]
+pub trait Codec {
+    const NAME: &'static str;
+    fn encode(&self);
+    type Error;
+    fn decode(&self);
+}
+
+fn main() {}
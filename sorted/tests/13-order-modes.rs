@@ -0,0 +1,70 @@
+// The comparison used by #[sorted] can be changed with `order = "..."`:
+//
+//   - "case_insensitive" ignores the case of letters,
+//   - "natural" compares runs of digits by their numeric value, so `Irq2`
+//     sorts before `Irq10`,
+//   - "descending" reverses the usual order.
+//
+// For match expressions the order can be given to #[sorted::check], applying
+// to every #[sorted] match inside, or to an individual #[sorted] match.
+// Wildcards and catch-all bindings sort last in every order.
+
+use sorted::sorted;
+
+#[sorted(order = "natural")]
+pub enum Interrupt {
+    Irq2,
+    Irq10,
+    Irq11,
+    Nmi,
+}
+
+#[sorted(order = "case_insensitive")]
+pub enum Error {
+    IoError,
+    IOLimit,
+    UTF8,
+    Unknown,
+}
+
+#[sorted(order = "descending")]
+#[derive(Clone, Copy)]
+pub enum Level {
+    Warn,
+    Info,
+    Trace,
+}
+
+#[sorted::check(order = "natural")]
+fn priority(irq: Interrupt) -> u8 {
+    #[sorted]
+    match irq {
+        Interrupt::Irq11 => 3,
+        Interrupt::Irq2 => 1,
+        Interrupt::Irq10 => 2,
+        Interrupt::Nmi => 0,
+    }
+}
+
+#[sorted::check]
+fn describe(irq: Interrupt) -> &'static str {
+    #[sorted(order = "natural")]
+    match irq {
+        Interrupt::Irq2 => "timer",
+        Interrupt::Irq10 => "network",
+        Interrupt::Irq11 => "disk",
+        Interrupt::Nmi => "fatal",
+    }
+}
+
+#[sorted::check]
+fn verbosity(level: Level) -> u8 {
+    #[sorted(order = "descending")]
+    match level {
+        Level::Warn => 0,
+        Level::Info => 1,
+        other => other as u8,
+    }
+}
+
+fn main() {}
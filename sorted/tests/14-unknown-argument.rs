@@ -0,0 +1,21 @@
+// Arguments that #[sorted] does not understand are reported where they were
+// written, instead of being silently ignored.
+
+use sorted::sorted;
+
+#[sorted(order = "random")]
+pub enum Direction {
+    East,
+    North,
+}
+
+#[sorted(reverse)]
+pub enum Color {
+    Blue,
+    Red,
+}
+
+#[sorted::check(strict)]
+fn f() {}
+
+fn main() {}
@@ -0,0 +1,31 @@
+// When a single element is out of place, only that element is reported, even
+// though every element after it compares less than it. The elements left
+// alone are the longest run that is already in order, so the errors name the
+// fewest elements that need to move.
+
+use sorted::sorted;
+
+#[sorted]
+pub enum Opcode {
+    Xor,
+    Add,
+    And,
+    Call,
+    Jump,
+    Load,
+    Or,
+    Store,
+    Sub,
+}
+
+#[sorted]
+pub enum Register {
+    Ax,
+    Cx,
+    Bx,
+    Dx,
+    Si,
+    Di,
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+// #[sorted] matches are found anywhere inside a #[sorted::check] function,
+// including inside the arms of another match, whether or not that outer match
+// is #[sorted] itself.
+
+use sorted::sorted;
+
+pub enum Shape {
+    Circle,
+    Square,
+}
+
+pub enum Color {
+    Blue,
+    Green,
+    Red,
+}
+
+#[sorted::check]
+fn in_sorted_arm(shape: Shape, color: Color) -> u8 {
+    #[sorted]
+    match shape {
+        Shape::Circle => 0,
+        Shape::Square => {
+            #[sorted]
+            match color {
+                Color::Red => 1,
+                Color::Blue => 2,
+                Color::Green => 3,
+            }
+        }
+    }
+}
+
+#[sorted::check]
+fn in_unsorted_match(shape: Shape, color: Color) -> u8 {
+    match shape {
+        Shape::Square => 0,
+        Shape::Circle => {
+            #[sorted]
+            match color {
+                Color::Blue => 1,
+                Color::Red => 2,
+                Color::Green => 3,
+            }
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,53 @@
+// Closures, async blocks and functions nested inside a #[sorted::check]
+// function are checked as well.
+
+use sorted::sorted;
+
+pub enum Signal {
+    Hangup,
+    Interrupt,
+    Kill,
+    Terminate,
+}
+
+#[sorted::check]
+fn in_closure() -> impl Fn(Signal) -> i32 {
+    |signal| {
+        #[sorted]
+        match signal {
+            Signal::Hangup => 1,
+            Signal::Kill => 9,
+            Signal::Interrupt => 2,
+            Signal::Terminate => 15,
+        }
+    }
+}
+
+#[sorted::check]
+fn in_async_block(signal: Signal) -> impl std::future::Future<Output = i32> {
+    async move {
+        #[sorted]
+        match signal {
+            Signal::Terminate => 15,
+            Signal::Hangup => 1,
+            Signal::Interrupt => 2,
+            Signal::Kill => 9,
+        }
+    }
+}
+
+#[sorted::check]
+fn in_nested_fn(signal: Signal) -> i32 {
+    fn number(signal: Signal) -> i32 {
+        #[sorted]
+        match signal {
+            Signal::Hangup => 1,
+            Signal::Interrupt => 2,
+            Signal::Terminate => 15,
+            Signal::Kill => 9,
+        }
+    }
+    number(signal)
+}
+
+fn main() {}
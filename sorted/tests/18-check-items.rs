@@ -0,0 +1,90 @@
+// #[sorted::check] can also be placed on an impl block, a trait or an inline
+// module, covering every function inside it with one attribute. Errors still
+// point at the match arm that is out of order. A function inside that sets its
+// own order with #[sorted::check(order = "...")] is checked with that order.
+
+use sorted::sorted;
+
+use std::fmt::{self, Display};
+
+pub enum Method {
+    Delete,
+    Get,
+    Post,
+}
+
+#[sorted::check]
+impl Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[sorted::check]
+impl Method {
+    fn name(&self) -> &'static str {
+        #[sorted]
+        match self {
+            Method::Get => "GET",
+            Method::Delete => "DELETE",
+            Method::Post => "POST",
+        }
+    }
+
+    fn is_safe(&self) -> bool {
+        #[sorted]
+        match self {
+            Method::Delete => false,
+            Method::Post => false,
+            Method::Get => true,
+        }
+    }
+
+    #[sorted::check(order = "descending")]
+    fn rank(&self) -> u8 {
+        #[sorted]
+        match self {
+            Method::Post => 0,
+            Method::Get => 1,
+            Method::Delete => 2,
+        }
+    }
+}
+
+#[sorted::check]
+pub trait Handler {
+    fn allows(&self, method: &Method) -> bool {
+        #[sorted]
+        match method {
+            Method::Post => false,
+            Method::Get => true,
+            Method::Delete => false,
+        }
+    }
+}
+
+#[sorted::check]
+mod routes {
+    use super::Method;
+
+    pub fn path(method: &Method) -> &'static str {
+        #[sorted]
+        match method {
+            Method::Delete => "/remove",
+            Method::Post => "/create",
+            Method::Get => "/read",
+        }
+    }
+
+    #[sorted::check(order = "descending")]
+    pub fn verb(method: &Method) -> &'static str {
+        #[sorted]
+        match method {
+            Method::Post => "create",
+            Method::Get => "read",
+            Method::Delete => "remove",
+        }
+    }
+}
+
+fn main() {}
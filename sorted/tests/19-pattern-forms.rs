@@ -0,0 +1,36 @@
+// Match arms commonly use or-patterns, `@` bindings and reference patterns.
+// These are sorted by the pattern they contain: an or-pattern by its first
+// alternative, with the alternatives themselves also required to be in order,
+// a binding by its subpattern, and a reference or parenthesized pattern by the
+// pattern inside.
+
+use std::io;
+
+pub enum Error {
+    Fmt,
+    Io(io::Error),
+    Parse,
+    Timeout,
+    Utf8,
+}
+
+#[sorted::check]
+fn retryable(err: &Error) -> bool {
+    #[sorted]
+    match err {
+        _io @ Error::Io(_) => true,
+        Error::Fmt | Error::Parse => false,
+        &(Error::Timeout | Error::Utf8) => true,
+    }
+}
+
+#[sorted::check]
+fn category(err: &Error) -> u8 {
+    #[sorted]
+    match err {
+        Error::Fmt | Error::Utf8 | Error::Parse => 0,
+        Error::Io(_) | Error::Timeout => 1,
+    }
+}
+
+fn main() {}
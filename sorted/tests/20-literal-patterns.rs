@@ -0,0 +1,83 @@
+// Matches on status codes, opcodes and command names use literal and range
+// patterns. These are sorted by value: integers, chars and bytes numerically,
+// ranges by where they start, and strings like names. Ranges that overlap an
+// earlier pattern are reported too, unless the arm has a guard.
+//
+// A catch-all binding like `other` goes last, like `_`, with any kind of
+// pattern. Patterns of different kinds cannot be compared, so mixing them is an
+// error.
+
+#[sorted::check]
+fn reason(status: i32) -> &'static str {
+    #[sorted]
+    match status {
+        -1 => "invalid",
+        100..200 => "informational",
+        200 => "ok",
+        204 => "no content",
+        201..=299 => "success",
+        404 => "not found",
+        300..400 => "redirect",
+        500.. => "server error",
+        _ => "client error",
+    }
+}
+
+#[sorted::check]
+fn opcode(op: u8) -> char {
+    #[sorted]
+    match op {
+        b'+' => '+',
+        b'-' => '-',
+        b'*' => '*',
+        b'0'..=b'9' => '0',
+        _ => '?',
+    }
+}
+
+#[sorted::check]
+fn command(name: &str) -> u8 {
+    #[sorted]
+    match name {
+        "get" => 0,
+        "del" => 1,
+        "set" => 2,
+        _ => 3,
+    }
+}
+
+#[sorted::check]
+fn class(c: char) -> u8 {
+    #[sorted]
+    match c {
+        '0'..='9' => 0,
+        'a'..='f' if c.is_ascii_lowercase() => 1,
+        'a'..='z' => 2,
+        _ => 3,
+    }
+}
+
+#[sorted::check]
+fn is_retryable(status: u16) -> bool {
+    #[sorted]
+    match status {
+        200 => false,
+        404 => false,
+        503 => true,
+        other => other >= 500,
+    }
+}
+
+const ZERO: u8 = 0;
+
+#[sorted::check]
+fn mixed(n: u8) -> u8 {
+    #[sorted]
+    match n {
+        ZERO => 0,
+        1 => 1,
+        _ => 2,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,69 @@
+// State machines match on a tuple of the state and the event. Tuple and slice
+// patterns are sorted element by element, like words in a dictionary, with `_`,
+// `..` and bindings sorting last in whatever position they appear. A slice that
+// is a prefix of another sorts first.
+
+#[derive(Clone, Copy)]
+pub enum State {
+    Idle,
+    Running,
+    Stopped,
+}
+
+#[derive(Clone, Copy)]
+pub enum Event {
+    Pause,
+    Start,
+    Stop,
+}
+
+#[sorted::check]
+fn next(state: State, event: Event) -> State {
+    use Event::*;
+    use State::*;
+
+    #[sorted]
+    match (state, event) {
+        (Idle, Start) => Running,
+        (Running, Stop) => Stopped,
+        (Running, Pause) => Idle,
+        (_, Stop) => Stopped,
+        (Stopped, _) => state,
+        _ => state,
+    }
+}
+
+#[sorted::check]
+fn sum(bytes: &[u8]) -> u8 {
+    #[sorted]
+    match bytes {
+        [] => 0,
+        [first, second] => first + second,
+        [only] => *only,
+        [first, ..] => *first,
+    }
+}
+
+const ZERO: u8 = 0;
+
+#[sorted::check]
+fn mixed(pair: (u8, u8)) -> u8 {
+    #[sorted]
+    match pair {
+        (0, ZERO) => 0,
+        (0, 1) => 1,
+        _ => 2,
+    }
+}
+
+#[sorted::check]
+fn offset(pair: (u8, u8)) -> u8 {
+    #[sorted]
+    match pair {
+        (0, n) => n,
+        (0, 1) => 1,
+        (a, b) => a + b,
+    }
+}
+
+fn main() {}
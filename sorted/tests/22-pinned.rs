@@ -0,0 +1,68 @@
+// Some variants belong at the end regardless of their name, like a catch-all
+// `Unknown`, and some have to stay where they are, like a legacy variant whose
+// discriminant must not change. Variants and match arms can be pinned with
+// `#[sorted(first)]` or `#[sorted(last)]`, or exempted from ordering with
+// `#[sorted(skip)]`. Pinned elements can be in any order among themselves, and
+// a wildcard arm can go before or after the arms pinned last.
+//
+// The markers are removed from the output.
+
+use sorted::sorted;
+
+#[sorted]
+#[repr(u8)]
+pub enum Opcode {
+    #[sorted(first)]
+    Nop,
+    Add,
+    #[sorted(skip)]
+    Legacy,
+    Jump,
+    Sub,
+    #[sorted(last)]
+    Unknown,
+    #[sorted(last)]
+    Invalid,
+}
+
+#[sorted]
+pub enum Token {
+    Ident,
+    #[sorted(last)]
+    Eof,
+    Number,
+    #[sorted(first)]
+    Bof,
+}
+
+#[sorted::check]
+fn cost(op: Opcode) -> u8 {
+    #[sorted]
+    match op {
+        Opcode::Add | Opcode::Sub => 1,
+        Opcode::Jump => 2,
+        #[sorted(skip)]
+        Opcode::Legacy => 9,
+        Opcode::Nop => 0,
+        #[sorted(last)]
+        Opcode::Unknown => 0,
+        _ => 0,
+    }
+}
+
+#[sorted::check]
+fn describe(token: Token) -> &'static str {
+    match token {
+        #[sorted(last)]
+        Token::Eof => "end",
+        _ => "token",
+    }
+}
+
+#[sorted]
+pub enum Malformed {
+    #[sorted(middle)]
+    A,
+}
+
+fn main() {}
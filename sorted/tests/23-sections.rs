@@ -0,0 +1,45 @@
+// Large enums are often grouped into sections, like I/O errors followed by
+// parse errors, that read best sorted on their own. `#[sorted(section)]` on a
+// variant or match arm starts a new section, checked independently of the ones
+// before it.
+
+use sorted::sorted;
+
+#[sorted]
+pub enum Error {
+    // I/O
+    Closed,
+    NotFound,
+    PermissionDenied,
+
+    // parsing
+    #[sorted(section)]
+    Eof,
+    InvalidDigit,
+    Overflow,
+}
+
+#[sorted]
+pub enum Event {
+    KeyDown,
+    KeyUp,
+
+    #[sorted(section)]
+    MouseMove,
+    MouseDown,
+    #[sorted(last)]
+    MouseLeave,
+}
+
+#[sorted::check]
+fn is_io(err: &Error) -> bool {
+    #[sorted]
+    match err {
+        Error::Closed | Error::NotFound | Error::PermissionDenied => true,
+        #[sorted(section)]
+        Error::Overflow => false,
+        Error::Eof | Error::InvalidDigit => false,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,110 @@
+// Sometimes the arms of a match should follow the order the variants are
+// declared in rather than the alphabet, for example when the enum lists the
+// stages of a pipeline. `#[sorted(declared)]` on the enum records the order of
+// its variants instead of checking it, and `#[sorted(as_declared = Enum)]` on a
+// match inside #[sorted::check] requires the arms to follow that order.
+//
+// The enum can be defined anywhere, even in another crate, so the order is
+// compared while the program is compiled, and the error is reported through a
+// constant that fails to evaluate. Plain bindings and `_` match any variant, so
+// they are left out of the comparison, and a variant named through another type
+// is an error. Generic enums are named without type arguments.
+
+use sorted::sorted;
+
+#[sorted(declared)]
+#[derive(Clone, Copy)]
+pub enum Stage {
+    Parse,
+    Resolve,
+    Typecheck,
+    Codegen,
+}
+
+#[sorted::check]
+fn describe(stage: Stage) -> &'static str {
+    #[sorted(as_declared = Stage)]
+    match stage {
+        Stage::Parse | Stage::Resolve => "front end",
+        Stage::Typecheck => "middle",
+        Stage::Codegen => "back end",
+    }
+}
+
+#[sorted::check]
+fn duration(stage: Stage) -> u32 {
+    #[sorted(as_declared = Stage)]
+    match stage {
+        Stage::Parse => 1,
+        Stage::Typecheck => 5,
+        Stage::Resolve => 2,
+        _ => 10,
+    }
+}
+
+#[sorted::check]
+fn is_front_end(stage: Stage) -> bool {
+    #[sorted(as_declared = Stage)]
+    match stage {
+        Stage::Parse => true,
+        other @ Stage::Resolve => matches!(other, Stage::Resolve),
+        _other => false,
+    }
+}
+
+pub enum Phase {
+    Parse,
+    Lower,
+}
+
+#[sorted::check]
+fn cost(phase: Phase) -> u32 {
+    #[sorted(as_declared = Stage)]
+    match phase {
+        Phase::Parse => 1,
+        Phase::Lower => 3,
+    }
+}
+
+#[sorted::check]
+fn imported_cost(phase: Phase) -> u32 {
+    use Phase::*;
+
+    #[sorted(as_declared = Stage)]
+    match phase {
+        Parse => 1,
+        Lower => 3,
+    }
+}
+
+#[sorted(declared)]
+pub enum Step<T> {
+    Start,
+    Run(T),
+    Finish,
+}
+
+#[sorted::check]
+fn start<T>(step: Step<T>) -> bool {
+    #[sorted(as_declared = Step)]
+    match step {
+        Step::Start => true,
+        Step::Run(_) | Step::Finish => false,
+    }
+}
+
+#[sorted::check]
+fn finish<T>(step: Step<T>) -> bool {
+    #[sorted(as_declared = Step)]
+    match step {
+        Step::Finish => true,
+        Step::Start | Step::Run(_) => false,
+    }
+}
+
+#[sorted(declared)]
+pub struct Config {
+    stage: Stage,
+}
+
+fn main() {}
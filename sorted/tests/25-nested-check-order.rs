@@ -0,0 +1,43 @@
+// A function nested inside a #[sorted::check] function that carries its own
+// #[sorted::check(order = "...")] is checked with that order, not the order of
+// the outer one.
+
+use sorted::sorted;
+
+pub enum Level {
+    Debug,
+    Error,
+    Info,
+}
+
+#[sorted::check]
+fn severity(level: Level) -> u8 {
+    #[sorted::check(order = "descending")]
+    fn inner(level: Level) -> u8 {
+        #[sorted]
+        match level {
+            Level::Info => 1,
+            Level::Error => 2,
+            Level::Debug => 0,
+        }
+    }
+
+    #[sorted::check(order = "descending")]
+    fn unsorted(level: Level) -> u8 {
+        #[sorted]
+        match level {
+            Level::Debug => 0,
+            Level::Error => 2,
+            Level::Info => 1,
+        }
+    }
+
+    #[sorted]
+    match level {
+        Level::Debug => 0,
+        Level::Error => inner(Level::Error),
+        Level::Info => unsorted(Level::Info),
+    }
+}
+
+fn main() {}
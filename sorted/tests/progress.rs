@@ -9,4 +9,21 @@ fn tests() {
     t.compile_fail("tests/06-pattern-path.rs");
     t.compile_fail("tests/07-unrecognized-pattern.rs");
     t.pass("tests/08-underscore.rs");
+    t.compile_fail("tests/09-struct-fields.rs");
+    t.compile_fail("tests/10-variant-fields.rs");
+    t.compile_fail("tests/11-impl-items.rs");
+    t.compile_fail("tests/12-group-by-kind.rs");
+    t.compile_fail("tests/13-order-modes.rs");
+    t.compile_fail("tests/14-unknown-argument.rs");
+    t.compile_fail("tests/15-minimal-moves.rs");
+    t.compile_fail("tests/16-nested-match.rs");
+    t.compile_fail("tests/17-nested-closures.rs");
+    t.compile_fail("tests/18-check-items.rs");
+    t.compile_fail("tests/19-pattern-forms.rs");
+    t.compile_fail("tests/20-literal-patterns.rs");
+    t.compile_fail("tests/21-tuple-patterns.rs");
+    t.compile_fail("tests/22-pinned.rs");
+    t.compile_fail("tests/23-sections.rs");
+    t.compile_fail("tests/24-declared-order.rs");
+    t.compile_fail("tests/25-nested-check-order.rs");
 }